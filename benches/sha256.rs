@@ -1,5 +1,10 @@
+use std::io::Cursor;
+
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
-use shs_rs::sha256::sha256;
+use shs_rs::sha256::{
+    compute_hash_with_backend, padding, set_backend_override, sha256, sha256_block32,
+    sha256_block64, sha256_reader, sha256_reader_with_capacity, sha256_x4, Backend, Sha256, IHV,
+};
 
 fn sha256_benchmark(c: &mut Criterion) {
     // Empty input
@@ -21,5 +26,125 @@ fn sha256_benchmark(c: &mut Criterion) {
     c.bench_function("sha256/1000 bytes", |b| b.iter(|| sha256(black_box(&odd_input))));
 }
 
-criterion_group!(benches, sha256_benchmark);
+fn backend_benchmark(c: &mut Criterion) {
+    let inputs = [("1KB", padding(&[0u8; 1024])), ("1MB", padding(&vec![0u8; 1024 * 1024]))];
+
+    // One group per backend this CPU supports
+    for backend in Backend::ALL.into_iter().filter(|backend| backend.is_available()) {
+        let mut group = c.benchmark_group(format!("compute_hash/{}", backend));
+        for (name, input) in &inputs {
+            group.bench_function(*name, |b| {
+                b.iter(|| compute_hash_with_backend(backend, IHV, black_box(input)))
+            });
+        }
+        group.finish();
+    }
+}
+
+fn fixed_size_benchmark(c: &mut Criterion) {
+    // Merkle tree leaves and nodes
+    let digest = [0u8; 32];
+    let node = [0u8; 64];
+    c.bench_function("sha256/32 bytes", |b| b.iter(|| sha256(black_box(&digest))));
+    c.bench_function("sha256_block32", |b| b.iter(|| sha256_block32(black_box(&digest))));
+    c.bench_function("sha256/64 bytes", |b| b.iter(|| sha256(black_box(&node))));
+    c.bench_function("sha256_block64", |b| b.iter(|| sha256_block64(black_box(&node))));
+}
+
+fn reader_buffer_benchmark(c: &mut Criterion) {
+    // 16 MB in memory, so only the number of reads differs
+    let data = vec![0u8; 16 * 1024 * 1024];
+    let mut group = c.benchmark_group("sha256_reader/16MB");
+    group.sample_size(20);
+    group.bench_function("8KB buffer", |b| {
+        b.iter(|| sha256_reader(Cursor::new(black_box(&data))).unwrap())
+    });
+    for (name, capacity) in [("64KB buffer", 64 * 1024), ("1MB buffer", 1024 * 1024)] {
+        group.bench_function(name, |b| {
+            b.iter(|| sha256_reader_with_capacity(Cursor::new(black_box(&data)), capacity).unwrap())
+        });
+    }
+    group.finish();
+}
+
+fn multi_buffer_benchmark(c: &mut Criterion) {
+    // Four Merkle-leaf sized messages
+    let leaves = [[0u8; 64], [1u8; 64], [2u8; 64], [3u8; 64]];
+    let inputs = leaves.each_ref().map(|leaf| &leaf[..]);
+
+    for backend in Backend::ALL.into_iter().filter(|backend| backend.is_available()) {
+        let mut group = c.benchmark_group(format!("sha256_x4/{}", backend));
+        set_backend_override(Some(backend)).unwrap();
+        group.bench_function("x4", |b| b.iter(|| sha256_x4(black_box(inputs))));
+        group.bench_function("4x sha256", |b| b.iter(|| black_box(inputs).map(sha256)));
+        group.finish();
+    }
+    set_backend_override(None).unwrap();
+}
+
+fn streaming_benchmark(c: &mut Criterion) {
+    // One-shot and streaming API on the same input
+    for (name, len) in [("small", 13), ("1KB", 1024), ("1MB", 1024 * 1024)] {
+        let input = vec![0u8; len];
+        let mut group = c.benchmark_group(format!("streaming/{}", name));
+        group.bench_function("sha256", |b| b.iter(|| sha256(black_box(&input))));
+        group.bench_function("new/update/finalize", |b| {
+            b.iter(|| {
+                let mut hasher = Sha256::new();
+                hasher.update(black_box(&input));
+                hasher.finalize()
+            })
+        });
+        group.finish();
+    }
+
+    // Per-update overhead, down to the pathological case of single-byte updates
+    let input = vec![0u8; 1024];
+    let mut group = c.benchmark_group("streaming/1KB in updates of");
+    for chunk_size in [1024, 64, 16, 1] {
+        group.bench_function(format!("{} bytes", chunk_size), |b| {
+            b.iter(|| {
+                let mut hasher = Sha256::new();
+                for chunk in black_box(&input).chunks(chunk_size) {
+                    hasher.update(chunk);
+                }
+                hasher.finalize()
+            })
+        });
+    }
+    group.finish();
+
+    // Many short messages, hashed with one reused hasher or a fresh one each
+    let messages: Vec<[u8; 32]> = (0..100).map(|i| [i; 32]).collect();
+    let mut group = c.benchmark_group("streaming/100x32 bytes");
+    group.bench_function("finalize_reset", |b| {
+        let mut hasher = Sha256::new();
+        b.iter(|| {
+            for message in black_box(&messages) {
+                hasher.update(message);
+                black_box(hasher.finalize_reset());
+            }
+        })
+    });
+    group.bench_function("fresh hasher", |b| {
+        b.iter(|| {
+            for message in black_box(&messages) {
+                let mut hasher = Sha256::new();
+                hasher.update(message);
+                black_box(hasher.finalize());
+            }
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    sha256_benchmark,
+    backend_benchmark,
+    fixed_size_benchmark,
+    multi_buffer_benchmark,
+    reader_buffer_benchmark,
+    streaming_benchmark
+);
 criterion_main!(benches);
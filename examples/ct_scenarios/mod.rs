@@ -0,0 +1,350 @@
+//! Constant-time scenarios shared by the `sha256_ct_bench` example and `tests/constant_time.rs`.
+//!
+//! Each scenario times two classes of inputs with dudect. Most take the [`Implementation`] to
+//! measure, so the example can run them against both this crate and `sha2`.
+
+// The tests only run some of the scenarios
+#![allow(dead_code)]
+
+use dudect_bencher::{BenchRng, Class, CtRunner};
+use rand::Rng;
+use shs_rs::sha256::{compress, sha256, sha256_fixed_time, verify, IHV};
+
+pub const ITERATIONS: u32 = 20_000;
+
+/// A SHA-256 implementation under test.
+pub struct Implementation {
+    /// One-shot hash of a message.
+    pub hash:     fn(&[u8]) -> [u8; 32],
+    /// Compression of whole 64-byte blocks into a hash value.
+    pub compress: fn(&mut [u32; 8], &[u8]),
+}
+
+impl Implementation {
+    /// Compress whole blocks starting from the initial hash value.
+    pub fn compress_from_iv(&self, blocks: &[u8]) -> [u32; 8] {
+        let mut state = IHV;
+        (self.compress)(&mut state, blocks);
+        state
+    }
+}
+
+/// This crate.
+pub const OURS: Implementation = Implementation {
+    hash:     sha256,
+    compress: |state, blocks| {
+        for block in blocks.chunks_exact(64) {
+            compress(state, block.try_into().unwrap());
+        }
+    },
+};
+
+pub fn rand_vec(len: usize, rng: &mut BenchRng) -> Vec<u8> {
+    let mut arr = vec![0u8; len];
+    rng.fill(arr.as_mut_slice());
+    arr
+}
+
+pub fn run_scenario(
+    runner: &mut CtRunner,
+    rng: &mut BenchRng,
+    imp: &Implementation,
+    len_left: usize,
+    len_right: usize,
+) {
+    for _ in 0..ITERATIONS {
+        let left = rand_vec(len_left, rng);
+        let right = rand_vec(len_right, rng);
+
+        runner.run_one(Class::Left, || {
+            let _ = (imp.hash)(&left);
+        });
+        runner.run_one(Class::Right, || {
+            let _ = (imp.hash)(&right);
+        });
+    }
+}
+
+pub fn length_extremes(runner: &mut CtRunner, rng: &mut BenchRng, imp: &Implementation) {
+    run_scenario(runner, rng, imp, 1, 1000);
+}
+
+pub fn single_bit_difference(runner: &mut CtRunner, rng: &mut BenchRng, imp: &Implementation) {
+    for _ in 0..ITERATIONS {
+        let left = rand_vec(64, rng);
+        let mut right = left.clone();
+
+        let byte_to_change = rng.gen_range(0..right.len());
+        let bit_to_change = rng.gen_range(0..8);
+        right[byte_to_change] ^= 1 << bit_to_change;
+
+        runner.run_one(Class::Left, || {
+            let _ = (imp.hash)(&left);
+        });
+        runner.run_one(Class::Right, || {
+            let _ = (imp.hash)(&right);
+        });
+    }
+}
+
+pub fn padding_behavior(runner: &mut CtRunner, rng: &mut BenchRng, imp: &Implementation) {
+    // Test inputs that trigger different padding behaviors
+    run_scenario(runner, rng, imp, 55, 56); // Before block boundary
+    run_scenario(runner, rng, imp, 63, 64); // Block boundary
+    run_scenario(runner, rng, imp, 119, 120); // Two block boundary
+}
+
+pub fn special_values_all_zeros(runner: &mut CtRunner, rng: &mut BenchRng, imp: &Implementation) {
+    for _ in 0..ITERATIONS {
+        let special = vec![0u8; 64]; // All zeros
+        let random = rand_vec(64, rng);
+
+        runner.run_one(Class::Left, || {
+            let _ = (imp.hash)(&special);
+        });
+        runner.run_one(Class::Right, || {
+            let _ = (imp.hash)(&random);
+        });
+    }
+}
+
+pub fn special_values_all_ones(runner: &mut CtRunner, rng: &mut BenchRng, imp: &Implementation) {
+    for _ in 0..ITERATIONS {
+        let special = vec![0xFFu8; 64]; // All ones
+        let random = rand_vec(64, rng);
+
+        runner.run_one(Class::Left, || {
+            let _ = (imp.hash)(&special);
+        });
+        runner.run_one(Class::Right, || {
+            let _ = (imp.hash)(&random);
+        });
+    }
+}
+
+pub fn special_values_alternating_bits(
+    runner: &mut CtRunner,
+    rng: &mut BenchRng,
+    imp: &Implementation,
+) {
+    for _ in 0..ITERATIONS {
+        let special = vec![0xAAu8; 64]; // Alternating bits (10101010)
+        let random = rand_vec(64, rng);
+
+        runner.run_one(Class::Left, || {
+            let _ = (imp.hash)(&special);
+        });
+        runner.run_one(Class::Right, || {
+            let _ = (imp.hash)(&random);
+        });
+    }
+}
+
+pub fn special_values_single_one(runner: &mut CtRunner, rng: &mut BenchRng, imp: &Implementation) {
+    for _ in 0..ITERATIONS {
+        let mut special = vec![0u8; 64];
+        special[63] = 1; // Only the last bit is 1
+        let random = rand_vec(64, rng);
+
+        runner.run_one(Class::Left, || {
+            let _ = (imp.hash)(&special);
+        });
+        runner.run_one(Class::Right, || {
+            let _ = (imp.hash)(&random);
+        });
+    }
+}
+
+pub fn special_values_high_low_bytes(
+    runner: &mut CtRunner,
+    rng: &mut BenchRng,
+    imp: &Implementation,
+) {
+    for _ in 0..ITERATIONS {
+        let mut special = vec![0u8; 64];
+        for (i, byte) in special.iter_mut().enumerate().take(64) {
+            *byte = if i % 2 == 0 { 0x00 } else { 0xFF };
+        }
+        let random = rand_vec(64, rng);
+
+        runner.run_one(Class::Left, || {
+            let _ = (imp.hash)(&special);
+        });
+        runner.run_one(Class::Right, || {
+            let _ = (imp.hash)(&random);
+        });
+    }
+}
+
+pub fn special_values_ascending(runner: &mut CtRunner, rng: &mut BenchRng, imp: &Implementation) {
+    for _ in 0..ITERATIONS {
+        let special: Vec<u8> = (0..64).map(|i| i as u8).collect();
+        let random = rand_vec(64, rng);
+
+        runner.run_one(Class::Left, || {
+            let _ = (imp.hash)(&special);
+        });
+        runner.run_one(Class::Right, || {
+            let _ = (imp.hash)(&random);
+        });
+    }
+}
+
+pub fn length_dependent_timing(runner: &mut CtRunner, rng: &mut BenchRng, imp: &Implementation) {
+    // Test a range of lengths to detect any length-dependent timing
+    for i in 1..=64 {
+        let left = rand_vec(i, rng);
+        let right = rand_vec(64, rng); // Fixed length for comparison
+
+        runner.run_one(Class::Left, || {
+            let _ = (imp.hash)(&left);
+        });
+        runner.run_one(Class::Right, || {
+            let _ = (imp.hash)(&right);
+        });
+    }
+}
+
+/// Messages of equal lengths with fixed and with random contents.
+///
+/// Unlike [`length_dependent_timing`], both classes share each length, from no block to three, so
+/// only the contents differ. The length is public and may change the running time, the contents
+/// may not.
+pub fn content_across_lengths(runner: &mut CtRunner, rng: &mut BenchRng, imp: &Implementation) {
+    for _ in 0..ITERATIONS / 160 {
+        for len in 0..160 {
+            let left = vec![0u8; len];
+            let right = rand_vec(len, rng);
+
+            runner.run_one(Class::Left, || {
+                let _ = (imp.hash)(&left);
+            });
+            runner.run_one(Class::Right, || {
+                let _ = (imp.hash)(&right);
+            });
+        }
+    }
+}
+
+pub fn block_processing_consistency(
+    runner: &mut CtRunner,
+    rng: &mut BenchRng,
+    imp: &Implementation,
+) {
+    // Compare single-block vs multi-block processing
+    run_scenario(runner, rng, imp, 63, 65); // Single vs two blocks
+    run_scenario(runner, rng, imp, 64, 128); // One vs two full blocks
+}
+
+pub fn intermediate_state_dependency(
+    runner: &mut CtRunner,
+    rng: &mut BenchRng,
+    imp: &Implementation,
+) {
+    for _ in 0..ITERATIONS {
+        let left = rand_vec(128, rng);
+        let mut right = left.clone();
+
+        // Modify the second block, which should affect intermediate state
+        for byte in right.iter_mut().skip(64).take(64) {
+            *byte = rng.gen();
+        }
+
+        runner.run_one(Class::Left, || {
+            let _ = (imp.hash)(&left);
+        });
+        runner.run_one(Class::Right, || {
+            let _ = (imp.hash)(&right);
+        });
+    }
+}
+
+pub fn compression_function_test(runner: &mut CtRunner, rng: &mut BenchRng, imp: &Implementation) {
+    for _ in 0..ITERATIONS {
+        let block1 = rand_vec(64, rng);
+        let block2 = rand_vec(64, rng);
+
+        runner.run_one(Class::Left, || {
+            let _ = imp.compress_from_iv(&block1);
+        });
+        runner.run_one(Class::Right, || {
+            let _ = imp.compress_from_iv(&block2);
+        });
+    }
+}
+
+pub fn compression_function_multiple_blocks(
+    runner: &mut CtRunner,
+    rng: &mut BenchRng,
+    imp: &Implementation,
+) {
+    for _ in 0..ITERATIONS {
+        let blocks1 = rand_vec(128, rng);
+        let blocks2 = rand_vec(128, rng);
+
+        runner.run_one(Class::Left, || {
+            let _ = imp.compress_from_iv(&blocks1);
+        });
+        runner.run_one(Class::Right, || {
+            let _ = imp.compress_from_iv(&blocks2);
+        });
+    }
+}
+
+pub fn compression_function_special_patterns(
+    runner: &mut CtRunner,
+    rng: &mut BenchRng,
+    imp: &Implementation,
+) {
+    for _ in 0..ITERATIONS {
+        let mut special_block = vec![0u8; 64];
+        let random_block = rand_vec(64, rng);
+
+        // Test with all zeros, all ones, and alternating bits
+        for pattern in &[0x00, 0xFF, 0xAA] {
+            special_block.fill(*pattern);
+
+            runner.run_one(Class::Left, || {
+                let _ = imp.compress_from_iv(&special_block);
+            });
+            runner.run_one(Class::Right, || {
+                let _ = imp.compress_from_iv(&random_block);
+            });
+        }
+    }
+}
+
+/// Digest comparison has no counterpart in `sha2`, so only this crate's [`verify`] is measured.
+pub fn digest_comparison(runner: &mut CtRunner, rng: &mut BenchRng) {
+    for _ in 0..ITERATIONS {
+        let expected = sha256(&rand_vec(64, rng));
+        let equal = expected;
+        let mut first_byte_differs = expected;
+        first_byte_differs[0] ^= 1;
+
+        runner.run_one(Class::Left, || {
+            let _ = verify(&expected, &equal);
+        });
+        runner.run_one(Class::Right, || {
+            let _ = verify(&expected, &first_byte_differs);
+        });
+    }
+}
+
+/// Messages of different lengths hashed with the same public maximum length, which
+/// [`sha256_fixed_time`] is meant to hide. `sha2` has no counterpart.
+pub fn fixed_time_length_hiding(runner: &mut CtRunner, rng: &mut BenchRng) {
+    const MAX_LEN: usize = 200;
+    for _ in 0..ITERATIONS {
+        // One versus four blocks of padded message, out of four compressed
+        let left = rand_vec(10, rng);
+        let right = rand_vec(MAX_LEN, rng);
+
+        runner.run_one(Class::Left, || {
+            let _ = sha256_fixed_time(&left, MAX_LEN);
+        });
+        runner.run_one(Class::Right, || {
+            let _ = sha256_fixed_time(&right, MAX_LEN);
+        });
+    }
+}
@@ -0,0 +1,33 @@
+//! Generate SHA_d-256 test vectors in the format of `SHAd256_Test_Vectors.txt`.
+//!
+//! Usage: `cargo run --example gen_vectors -- <count> <min_len> <max_len>`
+//!
+//! Prints `count` vectors whose inputs are prefixes of the zero-key RC4 keystream, with lengths
+//! spread evenly from `min_len` to `max_len`. Lines for the lengths in the committed file are
+//! identical to it.
+
+use std::{env, process};
+
+#[path = "../tests/common/mod.rs"] mod common;
+use common::{format_vector, rc4_keystream};
+
+fn parse_args() -> Option<(usize, usize, usize)> {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let [count, min_len, max_len] = args.as_slice() else { return None };
+    let (count, min_len, max_len) =
+        (count.parse().ok()?, min_len.parse().ok()?, max_len.parse().ok()?);
+    (count > 0 && min_len <= max_len).then_some((count, min_len, max_len))
+}
+
+fn main() {
+    let Some((count, min_len, max_len)) = parse_args() else {
+        eprintln!("usage: gen_vectors <count> <min_len> <max_len>");
+        process::exit(2);
+    };
+
+    let keystream = rc4_keystream(max_len);
+    for i in 0..count {
+        let len = min_len + (max_len - min_len) * i / (count - 1).max(1);
+        println!("{}", format_vector(&format!("RC4.{}", len), "RC4", &keystream[..len]));
+    }
+}
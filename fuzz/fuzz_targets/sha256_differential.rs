@@ -0,0 +1,12 @@
+//! Compare the one-shot `sha256` against the `sha2` crate on arbitrary input.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use sha2::Digest;
+use shs_rs::sha256::sha256;
+
+fuzz_target!(|data: &[u8]| {
+    let expected: [u8; 32] = sha2::Sha256::digest(data).into();
+    assert_eq!(sha256(data), expected);
+});
@@ -0,0 +1,29 @@
+//! Compare the streaming `Sha256` over arbitrary splits of a message against the one-shot result.
+//!
+//! The first byte of the input is the number of chunk lengths that follow, the rest is the message.
+//! Chunk lengths are used in turn, zero-length updates included, and whatever is left of the
+//! message is absorbed last.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use shs_rs::sha256::{sha256, Sha256};
+
+fuzz_target!(|data: &[u8]| {
+    let Some((&count, rest)) = data.split_first() else {
+        return;
+    };
+    let (lengths, mut message) = rest.split_at((count as usize).min(rest.len()));
+    let expected = sha256(message);
+
+    // Zero-length chunks don't consume the message, so bound the number of updates
+    let mut hasher = Sha256::new();
+    for &len in lengths.iter().cycle().take(message.len() + lengths.len()) {
+        let (chunk, tail) = message.split_at((len as usize).min(message.len()));
+        hasher.update(chunk);
+        message = tail;
+    }
+    hasher.update(message);
+
+    assert_eq!(hasher.finalize(), expected);
+});
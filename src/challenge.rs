@@ -0,0 +1,66 @@
+//! Random challenges for challenge-response authentication.
+//!
+//! A challenge binds a fresh 32-byte nonce to a context, e.g. a session id or the name of the
+//! resource being accessed, as `sha256(context || nonce)`. The verifier keeps the nonce, sends the
+//! challenge, and recomputes it from the context and nonce when the response comes back.
+//!
+//! # Examples
+//!
+//! ```
+//! use shs_rs::{challenge::gen_challenge, sha256::Sha256};
+//!
+//! let (nonce, challenge) = gen_challenge(&mut rand::thread_rng(), b"login:alice");
+//! assert_eq!(challenge, Sha256::new().chain(b"login:alice").chain(&nonce).finalize());
+//! ```
+
+use rand_core::{CryptoRng, RngCore};
+
+use crate::sha256::Sha256;
+
+/// Generate a random nonce and the challenge derived from it.
+///
+/// # Parameters
+///
+/// - `rng`: Cryptographically secure source of the nonce.
+/// - `context`: Data the challenge is bound to.
+///
+/// # Returns
+///
+/// The nonce and `sha256(context || nonce)`.
+#[must_use]
+pub fn gen_challenge<R: RngCore + CryptoRng>(rng: &mut R, context: &[u8]) -> ([u8; 32], [u8; 32]) {
+    let mut nonce = [0u8; 32];
+    rng.fill_bytes(&mut nonce);
+    (nonce, Sha256::new().chain(context).chain(&nonce).finalize())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{sha256::sha256, test_rng::test_rng};
+
+    #[test]
+    fn test_gen_challenge() {
+        let mut rng = test_rng();
+        let (nonce, challenge) = gen_challenge(&mut rng, b"context");
+        assert_eq!(
+            hex::encode(nonce),
+            "e7bd29cae52b1a378d2a8921ffce4d4a12ae02146ba7756583ca88758f5a5b26"
+        );
+        assert_eq!(
+            hex::encode(challenge),
+            "7d8c2b877e9a3858c036e3e1b0c9ff0952ee2c6cbfc8f597c75b43011ff15989"
+        );
+        assert_eq!(challenge, sha256(&[&b"context"[..], &nonce].concat()));
+
+        // Fresh nonces make fresh challenges for the same context
+        let (other_nonce, other_challenge) = gen_challenge(&mut rng, b"context");
+        assert_ne!(other_nonce, nonce);
+        assert_ne!(other_challenge, challenge);
+
+        // The nonce is drawn from the given generator
+        let (again, _) = gen_challenge(&mut test_rng(), b"");
+        assert_eq!(again, nonce);
+        assert_eq!(gen_challenge(&mut test_rng(), b"").1, sha256(&nonce));
+    }
+}
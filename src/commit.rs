@@ -0,0 +1,125 @@
+//! Hash-based commitments for commit/reveal protocols.
+//!
+//! A commitment to `message` is `sha256(randomness || message)` for 32 fresh random bytes. It is
+//! computationally hiding, since learning anything about `message` from the commitment requires
+//! finding a SHA-256 preimage, and binding, since opening it to a different message requires a
+//! SHA-256 collision. Hiding only holds if the randomness is uniformly random, secret until the
+//! reveal, and never reused.
+//!
+//! # Examples
+//!
+//! ```
+//! use shs_rs::commit::{commit, verify_commitment};
+//!
+//! let randomness = [7u8; 32]; // use fresh randomness in practice
+//! let commitment = commit(b"heads", &randomness);
+//!
+//! // Later, reveal the message and randomness
+//! assert!(verify_commitment(&commitment, b"heads", &randomness));
+//! assert!(!verify_commitment(&commitment, b"tails", &randomness));
+//! ```
+
+use crate::sha256::{verify, Sha256};
+
+/// Commitment to a message, see [`commit`].
+pub type Commitment = [u8; 32];
+
+/// Commit to a message.
+///
+/// # Parameters
+///
+/// - `message`: Message to commit to.
+/// - `randomness`: Uniformly random blinding value, kept secret until the message is revealed.
+///
+/// # Returns
+///
+/// `sha256(randomness || message)`.
+#[must_use]
+pub fn commit(message: &[u8], randomness: &[u8; 32]) -> Commitment {
+    let mut hasher = Sha256::new();
+    hasher.update(randomness);
+    hasher.update(message);
+    hasher.finalize()
+}
+
+/// Check a revealed message and randomness against a commitment, in constant time.
+///
+/// # Parameters
+///
+/// - `commitment`: Commitment received before the reveal.
+/// - `message`: Revealed message.
+/// - `randomness`: Revealed blinding value.
+///
+/// # Returns
+///
+/// Whether `commitment` was computed from `message` and `randomness`.
+#[must_use]
+pub fn verify_commitment(commitment: &Commitment, message: &[u8], randomness: &[u8; 32]) -> bool {
+    verify(commitment, &commit(message, randomness)).into()
+}
+
+/// Commit to a message with fresh randomness.
+///
+/// # Parameters
+///
+/// - `rng`: Source of the blinding value, which should be cryptographically secure.
+/// - `message`: Message to commit to.
+///
+/// # Returns
+///
+/// The commitment and the randomness needed to open it.
+///
+/// # Examples
+///
+/// ```
+/// use shs_rs::commit::{commit_random, verify_commitment};
+///
+/// let (commitment, randomness) = commit_random(&mut rand::thread_rng(), b"heads");
+/// assert!(verify_commitment(&commitment, b"heads", &randomness));
+/// ```
+#[cfg(feature = "rand")]
+#[must_use]
+pub fn commit_random<R: rand_core::RngCore>(rng: &mut R, message: &[u8]) -> (Commitment, [u8; 32]) {
+    let mut randomness = [0u8; 32];
+    rng.fill_bytes(&mut randomness);
+    (commit(message, &randomness), randomness)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::sha256::sha256;
+    #[cfg(feature = "rand")] use crate::test_rng::test_rng;
+
+    #[test]
+    fn test_commit() {
+        let randomness = [0x42u8; 32];
+        let commitment = commit(b"message", &randomness);
+        assert_eq!(commitment, sha256(&[&randomness[..], b"message"].concat()));
+        assert_eq!(commit(b"message", &randomness), commitment);
+
+        assert!(verify_commitment(&commitment, b"message", &randomness));
+        assert!(!verify_commitment(&commitment, b"messagf", &randomness));
+        assert!(!verify_commitment(&commitment, b"message", &[0x43u8; 32]));
+    }
+
+    #[test]
+    #[cfg(feature = "rand")]
+    fn test_commit_random() {
+        let mut rng = test_rng();
+        let (commitment, randomness) = commit_random(&mut rng, b"message");
+        assert_eq!(
+            hex::encode(randomness),
+            "e7bd29cae52b1a378d2a8921ffce4d4a12ae02146ba7756583ca88758f5a5b26"
+        );
+        assert_eq!(
+            hex::encode(commitment),
+            "b4a8b9a169097750fbe3529bb3eb96dcc2b61a7fd0acc411c5a679df0d9bd8d3"
+        );
+        assert_eq!(commit(b"message", &randomness), commitment);
+        assert!(!verify_commitment(&commitment, b"", &randomness));
+
+        // Commitments to the same message don't repeat
+        assert_ne!(commit_random(&mut rng, b"message").0, commitment);
+    }
+}
@@ -0,0 +1,138 @@
+//! Hex encoding and decoding without secret-dependent branches or table lookups.
+//!
+//! Digests of secret data are themselves secret, e.g. MAC tags, so converting them to and from
+//! text should not leak through timing either. Each nibble is mapped to and from its ASCII digit
+//! with arithmetic only.
+
+/// Encode a nibble as an ASCII hex digit.
+///
+/// # Parameters
+///
+/// - `nibble`: Value in `0..16`.
+/// - `upper`: Whether to use `A-F` rather than `a-f`.
+///
+/// # Returns
+///
+/// The ASCII hex digit of `nibble`.
+#[inline]
+pub(crate) fn hex_digit(nibble: u8, upper: bool) -> u8 {
+    let nibble = nibble as i16;
+    let alpha = if upper { b'A' } else { b'a' } as i16;
+
+    // All ones if the nibble is above 9, shifting the digit from `0-9` up to the letters
+    let is_letter = (9 - nibble) >> 8;
+    (nibble + b'0' as i16 + (is_letter & (alpha - b'0' as i16 - 10))) as u8
+}
+
+/// Encode bytes as hex into a caller-provided buffer.
+///
+/// # Parameters
+///
+/// - `bytes`: Bytes to encode.
+/// - `out`: Buffer of exactly `2 * bytes.len()` bytes receiving the ASCII hex digits.
+/// - `upper`: Whether to use `A-F` rather than `a-f`.
+pub(crate) fn encode_hex(bytes: &[u8], out: &mut [u8], upper: bool) {
+    assert_eq!(out.len(), 2 * bytes.len(), "hex buffer must be twice the input length");
+    for (byte, digits) in bytes.iter().zip(out.chunks_exact_mut(2)) {
+        digits[0] = hex_digit(byte >> 4, upper);
+        digits[1] = hex_digit(byte & 0x0f, upper);
+    }
+}
+
+/// All ones if `lo <= c <= hi`, zero otherwise.
+#[inline]
+fn range_mask(c: i16, lo: u8, hi: u8) -> i16 { ((lo as i16 - 1 - c) & (c - hi as i16 - 1)) >> 8 }
+
+/// Decode an ASCII hex digit of either case.
+///
+/// # Parameters
+///
+/// - `digit`: ASCII character.
+///
+/// # Returns
+///
+/// The nibble value, and all ones if `digit` is a hex digit or zero otherwise.
+#[inline]
+fn hex_value(digit: u8) -> (u8, u8) {
+    let c = digit as i16;
+    let decimal = range_mask(c, b'0', b'9');
+    let lower = range_mask(c, b'a', b'f');
+    let upper = range_mask(c, b'A', b'F');
+
+    let value = (decimal & (c - b'0' as i16))
+        | (lower & (c - b'a' as i16 + 10))
+        | (upper & (c - b'A' as i16 + 10));
+    (value as u8, (decimal | lower | upper) as u8)
+}
+
+/// Decode hex of either case into a caller-provided buffer.
+///
+/// Every digit is decoded even after an invalid one, so the time taken does not depend on where
+/// the input is malformed.
+///
+/// # Parameters
+///
+/// - `hex`: ASCII hex digits.
+/// - `out`: Buffer of exactly `hex.len() / 2` bytes receiving the decoded bytes.
+///
+/// # Returns
+///
+/// `false` if `hex` contains a character that is not a hex digit.
+pub(crate) fn decode_hex(hex: &[u8], out: &mut [u8]) -> bool {
+    assert_eq!(hex.len(), 2 * out.len(), "hex input must be twice the buffer length");
+    let mut valid = 0xff;
+    for (digits, byte) in hex.chunks_exact(2).zip(out.iter_mut()) {
+        let (high, high_valid) = hex_value(digits[0]);
+        let (low, low_valid) = hex_value(digits[1]);
+        *byte = (high << 4) | low;
+        valid &= high_valid & low_valid;
+    }
+    valid == 0xff
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_hex_digit() {
+        for nibble in 0..16u8 {
+            assert_eq!(
+                hex_digit(nibble, false) as char,
+                format!("{:x}", nibble).chars().next().unwrap()
+            );
+            assert_eq!(
+                hex_digit(nibble, true) as char,
+                format!("{:X}", nibble).chars().next().unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn test_encode_hex() {
+        let bytes: Vec<u8> = (0..=255).collect();
+        let mut out = vec![0u8; 2 * bytes.len()];
+
+        encode_hex(&bytes, &mut out, false);
+        assert_eq!(out, hex::encode(&bytes).into_bytes());
+        encode_hex(&bytes, &mut out, true);
+        assert_eq!(out, hex::encode_upper(&bytes).into_bytes());
+    }
+
+    #[test]
+    fn test_decode_hex() {
+        let bytes: Vec<u8> = (0..=255).collect();
+        let mut out = vec![0u8; bytes.len()];
+        assert!(decode_hex(hex::encode(&bytes).as_bytes(), &mut out));
+        assert_eq!(out, bytes);
+        assert!(decode_hex(hex::encode_upper(&bytes).as_bytes(), &mut out));
+        assert_eq!(out, bytes);
+
+        // Every non hex character is rejected, including those next to the digit ranges
+        for c in 0..=255u8 {
+            let valid = c.is_ascii_hexdigit();
+            assert_eq!(decode_hex(&[b'0', c], &mut [0u8]), valid, "Character {:#x} failed", c);
+            assert_eq!(decode_hex(&[c, b'0'], &mut [0u8]), valid, "Character {:#x} failed", c);
+        }
+    }
+}
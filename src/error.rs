@@ -0,0 +1,138 @@
+//! Crate-level error type.
+//!
+//! Each module reports its own specific error, e.g. [`HkdfError`]. All of them convert into
+//! [`ShaError`], so code using several modules can propagate them with `?` and match on a single
+//! type.
+
+use core::fmt;
+
+use crate::{
+    hkdf::HkdfError,
+    hmac::HmacError,
+    sha256::{ParseDigestError, StateError, UnsupportedBackend, BLOCK_SIZE, MAX_INPUT_LEN},
+};
+
+/// Any error returned by this crate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShaError {
+    /// A message block passed to [`try_compute_hash`](crate::sha256::try_compute_hash) is not
+    /// exactly [`BLOCK_SIZE`] bytes long.
+    InvalidBlockLength {
+        /// Position of the block.
+        index: usize,
+        /// Length of the block.
+        len:   usize,
+    },
+    /// A streaming message would exceed [`MAX_INPUT_LEN`] bytes.
+    InputTooLong(u64),
+    /// An HKDF operation failed, e.g. the requested output is too long.
+    Hkdf(HkdfError),
+    /// An HMAC tag has an unsupported length.
+    Hmac(HmacError),
+    /// A hex digest could not be parsed.
+    ParseDigest(ParseDigestError),
+    /// A [`Sha256State`](crate::sha256::Sha256State) could not be decoded or resumed.
+    State(StateError),
+    /// The selected backend is not supported by this CPU.
+    UnsupportedBackend(UnsupportedBackend),
+}
+
+impl fmt::Display for ShaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ShaError::InvalidBlockLength { index, len } =>
+                write!(f, "message block {} has length {}, expected {}", index, len, BLOCK_SIZE),
+            ShaError::InputTooLong(len) =>
+                write!(f, "input length {} exceeds maximum {} bytes", len, MAX_INPUT_LEN),
+            ShaError::Hkdf(err) => err.fmt(f),
+            ShaError::Hmac(err) => err.fmt(f),
+            ShaError::ParseDigest(err) => err.fmt(f),
+            ShaError::State(err) => err.fmt(f),
+            ShaError::UnsupportedBackend(err) => err.fmt(f),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ShaError {}
+
+/// Without `std` the same trait is available from `core`.
+#[cfg(not(feature = "std"))]
+impl core::error::Error for ShaError {}
+
+impl From<HkdfError> for ShaError {
+    fn from(err: HkdfError) -> Self { ShaError::Hkdf(err) }
+}
+
+impl From<HmacError> for ShaError {
+    fn from(err: HmacError) -> Self { ShaError::Hmac(err) }
+}
+
+impl From<ParseDigestError> for ShaError {
+    fn from(err: ParseDigestError) -> Self { ShaError::ParseDigest(err) }
+}
+
+impl From<StateError> for ShaError {
+    fn from(err: StateError) -> Self { ShaError::State(err) }
+}
+
+impl From<UnsupportedBackend> for ShaError {
+    fn from(err: UnsupportedBackend) -> Self { ShaError::UnsupportedBackend(err) }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        hkdf::{hkdf_expand, MAX_OUTPUT_LEN},
+        sha256::{Backend, Digest},
+    };
+
+    #[test]
+    fn test_display() {
+        assert_eq!(
+            ShaError::InvalidBlockLength { index: 2, len: 63 }.to_string(),
+            "message block 2 has length 63, expected 64"
+        );
+        assert_eq!(
+            ShaError::Hkdf(HkdfError::OutputTooLong(8161)).to_string(),
+            "HKDF output length 8161 exceeds maximum 8160"
+        );
+        assert_eq!(
+            ShaError::ParseDigest(ParseDigestError::InvalidLength(63)).to_string(),
+            "invalid digest length 63, expected 64 hex characters"
+        );
+        assert_eq!(
+            ShaError::UnsupportedBackend(UnsupportedBackend(Backend::Neon)).to_string(),
+            "NEON backend is not supported by this CPU"
+        );
+    }
+
+    #[test]
+    fn test_from_module_errors() {
+        fn derive_and_parse(hex: &str) -> Result<Digest, ShaError> {
+            let mut okm = vec![0u8; MAX_OUTPUT_LEN + 1];
+            if hex.is_empty() {
+                hkdf_expand(&[0u8; 32], b"", &mut okm)?;
+            }
+            Ok(hex.parse()?)
+        }
+
+        assert_eq!(
+            derive_and_parse(""),
+            Err(ShaError::Hkdf(HkdfError::OutputTooLong(MAX_OUTPUT_LEN + 1)))
+        );
+        assert_eq!(
+            derive_and_parse("abc"),
+            Err(ShaError::ParseDigest(ParseDigestError::InvalidLength(3)))
+        );
+        assert_eq!(
+            ShaError::from(StateError::UnsupportedVersion(9)),
+            ShaError::State(StateError::UnsupportedVersion(9))
+        );
+        assert_eq!(
+            ShaError::from(UnsupportedBackend(Backend::Neon)),
+            ShaError::UnsupportedBackend(UnsupportedBackend(Backend::Neon))
+        );
+    }
+}
@@ -0,0 +1,123 @@
+//! Length-extension attacks on SHA-256.
+//!
+//! A SHA-256 digest is the full internal state after the padded message, so anyone holding
+//! `sha256(m)` and the length of `m` can keep hashing from there and compute
+//! `sha256(m || glue || suffix)` without knowing `m`. This is why `sha256(secret || data)` is not
+//! a MAC; use [`crate::hmac`] instead.
+//!
+//! This module exists for teaching and testing only.
+//!
+//! # Examples
+//!
+//! ```
+//! use shs_rs::{extension::forge, sha256::sha256};
+//!
+//! let secret = b"secret key";
+//! let mac = sha256(&[&secret[..], b"amount=10"].concat());
+//!
+//! // The attacker only knows `mac` and the length of `secret || data`
+//! let (forged, glue) = forge(&mac, secret.len() + 9, b"&amount=1000");
+//!
+//! let message = [&secret[..], b"amount=10", &glue, b"&amount=1000"].concat();
+//! assert_eq!(forged, sha256(&message));
+//! ```
+
+use alloc::{vec, vec::Vec};
+
+use crate::sha256::{bytes_to_state, Sha256, BLOCK_SIZE};
+
+/// Padding appended by SHA-256 to a message of `len` bytes.
+///
+/// See: FIPS 180-4, 5.1.1
+///
+/// # Parameters
+///
+/// - `len`: Message length in bytes.
+///
+/// # Returns
+///
+/// The `0x80` byte, zeros, and the 64-bit big-endian message length in bits.
+pub fn glue_padding(len: usize) -> Vec<u8> {
+    // The "1" bit, zeros up to 56 bytes into a block, then the 64-bit length
+    let glue_len = 1 + (BLOCK_SIZE + 55 - len % BLOCK_SIZE) % BLOCK_SIZE + 8;
+    let mut glue = vec![0u8; glue_len];
+    glue[0] = 0x80;
+    glue[glue_len - 8..].copy_from_slice(&((len as u64) * 8).to_be_bytes());
+    glue
+}
+
+/// Extend a SHA-256 digest with `suffix` without knowing the original message.
+///
+/// # Parameters
+///
+/// - `original_digest`: Digest of the unknown message `m`.
+/// - `original_len`: Length of `m` in bytes.
+/// - `suffix`: Data to append.
+///
+/// # Returns
+///
+/// `sha256(m || glue || suffix)` and the `glue` padding bytes the forged message must contain.
+#[must_use]
+pub fn forge(
+    original_digest: &[u8; 32],
+    original_len: usize,
+    suffix: &[u8],
+) -> ([u8; 32], Vec<u8>) {
+    let glue = glue_padding(original_len);
+
+    // The digest is the state after the last padded block of `m`, so keep hashing from there as
+    // if `suffix` followed `m || glue`
+    let forged =
+        Sha256::from_raw_state(bytes_to_state(original_digest), (original_len + glue.len()) as u64)
+            .expect("m || glue is a whole number of blocks")
+            .chain(suffix)
+            .finalize();
+    (forged, glue)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{hmac::hmac_sha256, sha256::sha256};
+
+    #[test]
+    fn test_glue_padding() {
+        for len in 0..200 {
+            let glue = glue_padding(len);
+            assert_eq!((len + glue.len()) % BLOCK_SIZE, 0, "Length {} failed", len);
+            assert!((9..=BLOCK_SIZE + 8).contains(&glue.len()));
+        }
+    }
+
+    #[test]
+    fn test_forge() {
+        for secret_len in [0, 1, 16, 55, 56, 63, 64, 100] {
+            let secret: Vec<u8> = (0..secret_len).map(|i| i as u8).collect();
+            let original = [&secret[..], b"user=alice"].concat();
+            let digest = sha256(&original);
+
+            for suffix_len in [0, 1, 55, 64, 65, 200] {
+                let suffix = vec![b'!'; suffix_len];
+                let (forged, glue) = forge(&digest, original.len(), &suffix);
+
+                let reconstructed = [&original[..], &glue, &suffix].concat();
+                assert_eq!(
+                    forged,
+                    sha256(&reconstructed),
+                    "Lengths {}, {} failed",
+                    secret_len,
+                    suffix_len
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_forge_hmac_resists() {
+        // The same forgery against an HMAC tag does not verify
+        let (secret, data, suffix) = (b"secret key", b"user=alice", b"&admin=true");
+        let tag = hmac_sha256(secret, data);
+        let (forged, glue) = forge(&tag, data.len(), suffix);
+        assert_ne!(forged, hmac_sha256(secret, &[&data[..], &glue, suffix].concat()));
+    }
+}
@@ -0,0 +1,70 @@
+//! Common interface of the SHA-2 hash functions.
+//!
+//! Code written against [`HashFunction`] accepts any of [`Sha256`], [`Sha384`] and [`Sha512`],
+//! with [`OutputSize::OUTPUT_SIZE`] telling how long its digests are.
+//!
+//! # Examples
+//!
+//! ```
+//! use shs_rs::{hash::HashFunction, sha256::Sha256, sha512::Sha512};
+//!
+//! fn checksum<H: HashFunction>(data: &[u8]) -> H::Output { H::digest(data) }
+//!
+//! assert_eq!(checksum::<Sha256>(b"abc"), shs_rs::sha256::sha256(b"abc"));
+//! assert_eq!(checksum::<Sha512>(b"abc").len(), 64);
+//! ```
+//!
+//! [`Sha256`]: crate::sha256::Sha256
+//! [`Sha384`]: crate::sha512::Sha384
+//! [`Sha512`]: crate::sha512::Sha512
+
+/// Length of a hash function's digest.
+pub trait OutputSize {
+    /// Digest length in bytes, e.g. 32 for SHA-256.
+    const OUTPUT_SIZE: usize;
+}
+
+/// A one-shot hash function.
+pub trait HashFunction: OutputSize {
+    /// Digest, an array of [`OutputSize::OUTPUT_SIZE`] bytes.
+    type Output: AsRef<[u8]>;
+
+    /// Compute the digest of a message.
+    ///
+    /// # Parameters
+    ///
+    /// - `input`: Input message to hash.
+    ///
+    /// # Returns
+    ///
+    /// Digest of the `input`.
+    #[must_use]
+    fn digest(input: &[u8]) -> Self::Output;
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        sha256::{sha256, Sha256},
+        sha512::{sha384, sha512, Sha384, Sha512},
+    };
+
+    /// Hash with `H` and check the digest length against its declared size.
+    fn digest_len<H: HashFunction>(input: &[u8]) -> usize {
+        let digest = H::digest(input);
+        assert_eq!(digest.as_ref().len(), H::OUTPUT_SIZE);
+        digest.as_ref().len()
+    }
+
+    #[test]
+    fn test_hash_function() {
+        assert_eq!(digest_len::<Sha256>(b"abc"), 32);
+        assert_eq!(digest_len::<Sha384>(b"abc"), 48);
+        assert_eq!(digest_len::<Sha512>(b"abc"), 64);
+
+        assert_eq!(Sha256::digest(b"abc"), sha256(b"abc"));
+        assert_eq!(Sha384::digest(b"abc"), sha384(b"abc"));
+        assert_eq!(Sha512::digest(b"abc"), sha512(b"abc"));
+    }
+}
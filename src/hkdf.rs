@@ -0,0 +1,328 @@
+//! HKDF-SHA256 implementation based on RFC 5869.
+//!
+//! This module provides the HMAC-based extract-and-expand key derivation function built on top of
+//! [`HmacSha256`].
+//!
+//! # References
+//!
+//! - [RFC 5869: HKDF](https://www.rfc-editor.org/rfc/rfc5869)
+//!
+//! # Examples
+//!
+//! ```
+//! use shs_rs::hkdf::{hkdf_expand, hkdf_extract};
+//!
+//! let prk = hkdf_extract(Some(b"salt"), b"input key material");
+//! let mut okm = [0u8; 42];
+//! hkdf_expand(&prk, b"context", &mut okm).unwrap();
+//! println!("HKDF-SHA256 output: {:x?}", okm);
+//! ```
+
+use core::fmt;
+
+use crate::hmac::HmacSha256;
+
+/// Length of the HMAC-SHA256 output in bytes.
+const HASH_LEN: usize = 32;
+
+/// Maximum number of bytes [`hkdf_expand`] can produce, `255 * HashLen`.
+///
+/// See: RFC 5869, 2.3
+pub const MAX_OUTPUT_LEN: usize = 255 * HASH_LEN;
+
+/// Errors returned by HKDF operations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HkdfError {
+    /// Requested output length exceeds [`MAX_OUTPUT_LEN`].
+    OutputTooLong(usize),
+}
+
+impl fmt::Display for HkdfError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HkdfError::OutputTooLong(len) =>
+                write!(f, "HKDF output length {} exceeds maximum {}", len, MAX_OUTPUT_LEN),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for HkdfError {}
+
+#[cfg(not(feature = "std"))]
+impl core::error::Error for HkdfError {}
+
+/// Extract a pseudorandom key from input keying material.
+///
+/// See: RFC 5869, 2.2
+///
+/// # Parameters
+///
+/// - `salt`: Optional non-secret random value. Defaults to `HashLen` zero bytes.
+/// - `ikm`: Input keying material.
+///
+/// # Returns
+///
+/// A 256-bit pseudorandom key.
+#[must_use]
+pub fn hkdf_extract(salt: Option<&[u8]>, ikm: &[u8]) -> [u8; 32] {
+    let mut mac = HmacSha256::new(salt.unwrap_or(&[0u8; HASH_LEN]));
+    mac.update(ikm);
+    mac.finalize()
+}
+
+/// Expand a pseudorandom key into output keying material.
+///
+/// The output only depends on `prk`, `info` and `out.len()`; a shorter output is always a prefix
+/// of a longer one.
+///
+/// See: RFC 5869, 2.3
+///
+/// # Parameters
+///
+/// - `prk`: Pseudorandom key of at least `HashLen` bytes, usually from [`hkdf_extract`].
+/// - `info`: Optional context and application specific information.
+/// - `out`: Buffer to fill with output keying material.
+///
+/// # Returns
+///
+/// [`HkdfError::OutputTooLong`] if `out` is longer than [`MAX_OUTPUT_LEN`].
+pub fn hkdf_expand(prk: &[u8], info: &[u8], out: &mut [u8]) -> Result<(), HkdfError> {
+    HkdfExpand::new(prk, info).fill(out)
+}
+
+/// HKDF-Expand producing the output keying material incrementally.
+///
+/// Output is read with [`HkdfExpand::fill`] or byte by byte as an [`Iterator`], without fixing
+/// the total length up front. The bytes read so far are always the same as those of
+/// [`hkdf_expand`] into a buffer of that length.
+///
+/// See: RFC 5869, 2.3
+///
+/// # Examples
+///
+/// ```
+/// use shs_rs::hkdf::{hkdf_expand, hkdf_extract, HkdfExpand};
+///
+/// let prk = hkdf_extract(Some(b"salt"), b"input key material");
+/// let mut expand = HkdfExpand::new(&prk, b"context");
+/// let (mut encryption_key, mut mac_key) = ([0u8; 16], [0u8; 32]);
+/// expand.fill(&mut encryption_key)?;
+/// expand.fill(&mut mac_key)?;
+///
+/// let mut okm = [0u8; 48];
+/// hkdf_expand(&prk, b"context", &mut okm)?;
+/// assert_eq!(okm[..16], encryption_key);
+/// assert_eq!(okm[16..], mac_key);
+/// # Ok::<(), shs_rs::hkdf::HkdfError>(())
+/// ```
+pub struct HkdfExpand<'a> {
+    /// MAC keyed with the pseudorandom key, reset after every block.
+    mac:      HmacSha256,
+    info:     &'a [u8],
+    /// The last output block `T(counter)`.
+    block:    [u8; HASH_LEN],
+    /// Number of blocks computed so far.
+    counter:  u8,
+    /// Number of bytes output so far.
+    position: usize,
+}
+
+impl<'a> HkdfExpand<'a> {
+    /// Start expanding a pseudorandom key.
+    ///
+    /// # Parameters
+    ///
+    /// - `prk`: Pseudorandom key of at least `HashLen` bytes, usually from [`hkdf_extract`].
+    /// - `info`: Optional context and application specific information.
+    pub fn new(prk: &[u8], info: &'a [u8]) -> Self {
+        Self { mac: HmacSha256::new(prk), info, block: [0; HASH_LEN], counter: 0, position: 0 }
+    }
+
+    /// Compute the next block `T(i) = HMAC-Hash(PRK, T(i - 1) | info | i)`, with `T(0)` empty.
+    fn next_block(&mut self) {
+        if self.counter > 0 {
+            self.mac.update(&self.block);
+        }
+        self.mac.update(self.info);
+        self.counter += 1;
+        self.mac.update(&[self.counter]);
+        self.block = self.mac.finalize_reset();
+    }
+
+    /// Fill a buffer with the next bytes of output keying material.
+    ///
+    /// # Parameters
+    ///
+    /// - `out`: Buffer to fill.
+    ///
+    /// # Returns
+    ///
+    /// [`HkdfError::OutputTooLong`] with the total length that would have been output, without
+    /// consuming any output, if it exceeds [`MAX_OUTPUT_LEN`].
+    pub fn fill(&mut self, mut out: &mut [u8]) -> Result<(), HkdfError> {
+        let end = self.position.saturating_add(out.len());
+        if end > MAX_OUTPUT_LEN {
+            return Err(HkdfError::OutputTooLong(end));
+        }
+
+        while !out.is_empty() {
+            let offset = self.position % HASH_LEN;
+            if offset == 0 {
+                self.next_block();
+            }
+            let n = (HASH_LEN - offset).min(out.len());
+            out[..n].copy_from_slice(&self.block[offset..offset + n]);
+            out = &mut out[n..];
+            self.position += n;
+        }
+        Ok(())
+    }
+}
+
+/// Yields the output keying material byte by byte, ending after [`MAX_OUTPUT_LEN`] bytes.
+impl Iterator for HkdfExpand<'_> {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<u8> {
+        let mut byte = [0u8];
+        self.fill(&mut byte).ok()?;
+        Some(byte[0])
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = MAX_OUTPUT_LEN - self.position;
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for HkdfExpand<'_> {}
+
+/// Wipe the last output block.
+#[cfg(feature = "zeroize")]
+impl Drop for HkdfExpand<'_> {
+    fn drop(&mut self) { zeroize::Zeroize::zeroize(&mut self.block); }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    struct TestVector {
+        ikm:  Vec<u8>,
+        salt: Vec<u8>,
+        info: Vec<u8>,
+        prk:  &'static str,
+        okm:  &'static str,
+    }
+
+    /// RFC 5869, A.1 - A.3
+    fn rfc5869_vectors() -> Vec<TestVector> {
+        vec![
+            TestVector {
+                ikm: vec![0x0b; 22],
+                salt: (0x00..=0x0c).collect(),
+                info: (0xf0..=0xf9).collect(),
+                prk: "077709362c2e32df0ddc3f0dc47bba6390b6c73bb50f9c3122ec844ad7c2b3e5",
+                okm: "3cb25f25faacd57a90434f64d0362f2a2d2d0a90cf1a5a4c5db02d56ecc4c5bf34007208d5b887185865",
+            },
+            TestVector {
+                ikm: (0x00..=0x4f).collect(),
+                salt: (0x60..=0xaf).collect(),
+                info: (0xb0..=0xff).collect(),
+                prk: "06a6b88c5853361a06104c9ceb35b45cef760014904671014a193f40c15fc244",
+                okm: "b11e398dc80327a1c8e7f78c596a49344f012eda2d4efad8a050cc4c19afa97c59045a99cac7827271cb41c65e590e09da3275600c2f09b8367793a9aca3db71cc30c58179ec3e87c14c01d5c1f3434f1d87",
+            },
+            TestVector {
+                ikm: vec![0x0b; 22],
+                salt: vec![],
+                info: vec![],
+                prk: "19ef24a32c717b167f33a91d6f648bdf96596776afdb6377ac434c1c293ccb04",
+                okm: "8da4e775a563c18f715f802a063c5a31b8a11f5c5ee1879ec3454e5f3c738d2d9d201395faa4b61a96c8",
+            },
+        ]
+    }
+
+    #[test]
+    fn test_hkdf_rfc5869() {
+        for vector in rfc5869_vectors() {
+            let prk = hkdf_extract(Some(&vector.salt), &vector.ikm);
+            assert_eq!(hex::encode(prk), vector.prk);
+
+            let mut okm = vec![0u8; vector.okm.len() / 2];
+            hkdf_expand(&prk, &vector.info, &mut okm).unwrap();
+            assert_eq!(hex::encode(okm), vector.okm);
+        }
+    }
+
+    #[test]
+    fn test_hkdf_extract_default_salt() {
+        // An absent salt is equivalent to an empty salt, see RFC 5869, A.3
+        let ikm = [0x0b; 22];
+        assert_eq!(hkdf_extract(None, &ikm), hkdf_extract(Some(&[]), &ikm));
+    }
+
+    #[test]
+    fn test_hkdf_expand_prefix() {
+        let prk = hkdf_extract(None, b"ikm");
+        let mut long = [0u8; 100];
+        hkdf_expand(&prk, b"info", &mut long).unwrap();
+
+        for len in [0, 1, 31, 32, 33, 64, 99] {
+            let mut short = vec![0u8; len];
+            hkdf_expand(&prk, b"info", &mut short).unwrap();
+            assert_eq!(short, long[..len], "Length {} failed", len);
+        }
+    }
+
+    #[test]
+    fn test_hkdf_expand_max_length() {
+        let prk = hkdf_extract(None, b"ikm");
+        let mut out = vec![0u8; MAX_OUTPUT_LEN];
+        assert_eq!(hkdf_expand(&prk, b"info", &mut out), Ok(()));
+
+        let mut out = vec![0u8; MAX_OUTPUT_LEN + 1];
+        let err = hkdf_expand(&prk, b"info", &mut out).unwrap_err();
+        assert_eq!(err, HkdfError::OutputTooLong(MAX_OUTPUT_LEN + 1));
+        assert_eq!(err.to_string(), "HKDF output length 8161 exceeds maximum 8160");
+    }
+
+    #[test]
+    fn test_hkdf_expand_incremental() {
+        let prk = hkdf_extract(None, b"ikm");
+        let mut one_shot = [0u8; 200];
+        hkdf_expand(&prk, b"info", &mut one_shot).unwrap();
+
+        // Reads of every size, within and across blocks
+        for chunk_size in [1, 5, 31, 32, 33, 64, 200] {
+            let mut expand = HkdfExpand::new(&prk, b"info");
+            let mut incremental = [0u8; 200];
+            for chunk in incremental.chunks_mut(chunk_size) {
+                expand.fill(chunk).unwrap();
+            }
+            assert_eq!(incremental, one_shot, "Chunk size {} failed", chunk_size);
+        }
+
+        let bytes: Vec<u8> = HkdfExpand::new(&prk, b"info").take(200).collect();
+        assert_eq!(bytes, one_shot);
+    }
+
+    #[test]
+    fn test_hkdf_expand_incremental_max_length() {
+        let prk = hkdf_extract(None, b"ikm");
+        let mut expand = HkdfExpand::new(&prk, b"info");
+        let mut out = vec![0u8; MAX_OUTPUT_LEN - 1];
+        expand.fill(&mut out).unwrap();
+
+        // A rejected read consumes nothing
+        assert_eq!(expand.fill(&mut [0u8; 2]), Err(HkdfError::OutputTooLong(MAX_OUTPUT_LEN + 1)));
+        assert_eq!(expand.len(), 1);
+        let mut last = [0u8];
+        expand.fill(&mut last).unwrap();
+
+        let mut one_shot = vec![0u8; MAX_OUTPUT_LEN];
+        hkdf_expand(&prk, b"info", &mut one_shot).unwrap();
+        assert_eq!(last[0], one_shot[MAX_OUTPUT_LEN - 1]);
+        assert_eq!(expand.next(), None);
+    }
+}
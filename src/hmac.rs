@@ -0,0 +1,440 @@
+//! HMAC-SHA256 implementation based on RFC 2104.
+//!
+//! This module provides a keyed-hash message authentication code built on top of the streaming
+//! [`Sha256`] hasher.
+//!
+//! # References
+//!
+//! - [RFC 2104: HMAC](https://www.rfc-editor.org/rfc/rfc2104)
+//! - [RFC 4231: HMAC-SHA Test Vectors](https://www.rfc-editor.org/rfc/rfc4231)
+//!
+//! # Examples
+//!
+//! ```
+//! use shs_rs::hmac::hmac_sha256;
+//!
+//! let tag = hmac_sha256(b"key", b"Hello, world!");
+//! println!("HMAC-SHA256 tag: {:x?}", tag);
+//! ```
+
+use core::fmt;
+
+use subtle::{Choice, ConstantTimeEq};
+
+use crate::{
+    mac::Mac,
+    sha256::{verify, Sha256, BLOCK_SIZE},
+};
+
+/// Inner padding byte.
+///
+/// See: RFC 2104, 2
+const IPAD: u8 = 0x36;
+
+/// Outer padding byte.
+///
+/// See: RFC 2104, 2
+const OPAD: u8 = 0x5c;
+
+/// Shortest truncated tag accepted by [`HmacSha256::verify_truncated`], 64 bits.
+///
+/// RFC 2104, 5 recommends keeping at least half of the output, i.e. 16 bytes; pass a higher
+/// minimum to [`HmacSha256::verify_truncated_min`] to enforce that.
+pub const MIN_TRUNCATED_TAG_LEN: usize = 8;
+
+/// Errors returned by HMAC operations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HmacError {
+    /// A truncated tag is shorter than the required minimum.
+    TagTooShort {
+        /// Length of the tag.
+        len: usize,
+        /// Required minimum length.
+        min: usize,
+    },
+    /// A tag is longer than the 32-byte HMAC-SHA256 output.
+    TagTooLong(usize),
+}
+
+impl fmt::Display for HmacError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HmacError::TagTooShort { len, min } =>
+                write!(f, "HMAC tag length {} is below minimum {}", len, min),
+            HmacError::TagTooLong(len) => write!(f, "HMAC tag length {} exceeds maximum 32", len),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for HmacError {}
+
+#[cfg(not(feature = "std"))]
+impl core::error::Error for HmacError {}
+
+/// Incremental HMAC-SHA256.
+///
+/// The key is absorbed once into the inner and outer hashers when the MAC is created. Their
+/// midstates are kept, so [`HmacSha256::reset`] and [`HmacSha256::finalize_reset`] start over on
+/// the next message without re-keying.
+///
+/// # Examples
+///
+/// ```
+/// use shs_rs::hmac::{hmac_sha256, HmacSha256};
+///
+/// let mut mac = HmacSha256::new(b"key");
+/// mac.update(b"Hello, ");
+/// mac.update(b"world!");
+/// assert_eq!(mac.finalize(), hmac_sha256(b"key", b"Hello, world!"));
+/// ```
+#[derive(Clone)]
+pub struct HmacSha256 {
+    /// Inner hasher after the key block, before any message.
+    keyed_inner: Sha256,
+    /// Inner hasher absorbing the message.
+    inner:       Sha256,
+    /// Outer hasher after the key block.
+    outer:       Sha256,
+}
+
+impl HmacSha256 {
+    /// Create a MAC keyed with `key`.
+    ///
+    /// Keys longer than the block size are hashed first, shorter keys are zero-padded.
+    ///
+    /// See: RFC 2104, 2
+    ///
+    /// # Parameters
+    ///
+    /// - `key`: Secret key of any length.
+    pub fn new(key: &[u8]) -> Self {
+        let mut block = [0u8; BLOCK_SIZE];
+        if key.len() > BLOCK_SIZE {
+            let mut hasher = Sha256::new();
+            hasher.update(key);
+            block[..32].copy_from_slice(&hasher.finalize());
+        } else {
+            block[..key.len()].copy_from_slice(key);
+        }
+
+        // Derive both pads in place so the key is only ever held in `block`
+        let mut inner = Sha256::new();
+        block.iter_mut().for_each(|byte| *byte ^= IPAD);
+        inner.update(&block);
+
+        let mut outer = Sha256::new();
+        block.iter_mut().for_each(|byte| *byte ^= IPAD ^ OPAD);
+        outer.update(&block);
+
+        #[cfg(feature = "zeroize")]
+        zeroize::Zeroize::zeroize(&mut block);
+
+        Self { keyed_inner: inner.clone(), inner, outer }
+    }
+
+    /// Absorb more of the message.
+    ///
+    /// # Parameters
+    ///
+    /// - `data`: Next piece of the message.
+    pub fn update(&mut self, data: &[u8]) { self.inner.update(data); }
+
+    /// Compute the authentication tag.
+    ///
+    /// # Returns
+    ///
+    /// 256-bit tag over all data passed to [`HmacSha256::update`].
+    #[must_use]
+    pub fn finalize(self) -> [u8; 32] {
+        let Self { inner, mut outer, .. } = self;
+        outer.update(&inner.finalize());
+        outer.finalize()
+    }
+
+    /// Discard the absorbed message, keeping the key.
+    pub fn reset(&mut self) { self.inner = self.keyed_inner.clone(); }
+
+    /// Compute the authentication tag and reset for the next message.
+    ///
+    /// # Returns
+    ///
+    /// 256-bit tag over all data passed to [`HmacSha256::update`] since the last reset.
+    #[must_use]
+    pub fn finalize_reset(&mut self) -> [u8; 32] {
+        let inner = core::mem::replace(&mut self.inner, self.keyed_inner.clone());
+        let mut outer = self.outer.clone();
+        outer.update(&inner.finalize());
+        outer.finalize()
+    }
+
+    /// Compute the authentication tag and compare it to an expected one in constant time.
+    ///
+    /// # Parameters
+    ///
+    /// - `tag`: Tag received with the message.
+    ///
+    /// # Returns
+    ///
+    /// [`Choice`] holding `1` if `tag` is valid for the absorbed message and `0` otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use shs_rs::hmac::{hmac_sha256, HmacSha256};
+    ///
+    /// let tag = hmac_sha256(b"key", b"Hello, world!");
+    /// let mut mac = HmacSha256::new(b"key");
+    /// mac.update(b"Hello, world!");
+    /// assert!(bool::from(mac.verify(&tag)));
+    /// ```
+    #[must_use]
+    pub fn verify(self, tag: &[u8; 32]) -> Choice { verify(tag, &self.finalize()) }
+
+    /// Compute the authentication tag and compare a truncated tag to its prefix in constant time.
+    ///
+    /// See: RFC 2104, 5
+    ///
+    /// # Parameters
+    ///
+    /// - `tag`: Tag received with the message, the first `tag.len()` bytes of the full tag.
+    ///
+    /// # Returns
+    ///
+    /// [`Choice`] holding `1` if `tag` is a valid prefix for the absorbed message and `0`
+    /// otherwise, or [`HmacError`] if `tag` is shorter than [`MIN_TRUNCATED_TAG_LEN`] or longer
+    /// than 32 bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use shs_rs::hmac::{hmac_sha256, HmacSha256};
+    ///
+    /// let tag = hmac_sha256(b"key", b"Hello, world!");
+    /// let mut mac = HmacSha256::new(b"key");
+    /// mac.update(b"Hello, world!");
+    /// assert!(bool::from(mac.verify_truncated(&tag[..16])?));
+    /// # Ok::<(), shs_rs::hmac::HmacError>(())
+    /// ```
+    pub fn verify_truncated(self, tag: &[u8]) -> Result<Choice, HmacError> {
+        self.verify_truncated_min(tag, MIN_TRUNCATED_TAG_LEN)
+    }
+
+    /// Like [`HmacSha256::verify_truncated`], with a custom minimum tag length.
+    ///
+    /// # Parameters
+    ///
+    /// - `tag`: Tag received with the message, the first `tag.len()` bytes of the full tag.
+    /// - `min_len`: Shortest tag to accept, as required by the protocol.
+    ///
+    /// # Returns
+    ///
+    /// [`Choice`] holding `1` if `tag` is a valid prefix for the absorbed message and `0`
+    /// otherwise, or [`HmacError`] if `tag` is shorter than `min_len` or longer than 32 bytes.
+    pub fn verify_truncated_min(self, tag: &[u8], min_len: usize) -> Result<Choice, HmacError> {
+        if tag.len() > 32 {
+            return Err(HmacError::TagTooLong(tag.len()));
+        }
+        if tag.len() < min_len {
+            return Err(HmacError::TagTooShort { len: tag.len(), min: min_len });
+        }
+        Ok(self.finalize()[..tag.len()].ct_eq(tag))
+    }
+}
+
+impl Mac for HmacSha256 {
+    type Tag = [u8; 32];
+
+    fn new(key: &[u8]) -> Self { HmacSha256::new(key) }
+
+    fn update(&mut self, data: &[u8]) { HmacSha256::update(self, data) }
+
+    fn finalize_reset(&mut self) -> [u8; 32] { HmacSha256::finalize_reset(self) }
+
+    fn finalize(self) -> [u8; 32] { HmacSha256::finalize(self) }
+}
+
+/// Both keyed hashers wipe themselves on drop.
+#[cfg(feature = "zeroize")]
+impl zeroize::ZeroizeOnDrop for HmacSha256 {}
+
+/// Compute HMAC-SHA256 of a message.
+///
+/// # Parameters
+///
+/// - `key`: Secret key of any length.
+/// - `message`: Message to authenticate.
+///
+/// # Returns
+///
+/// 256-bit authentication tag.
+#[must_use]
+pub fn hmac_sha256(key: &[u8], message: &[u8]) -> [u8; 32] {
+    let mut mac = HmacSha256::new(key);
+    mac.update(message);
+    mac.finalize()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::sha256::sha256;
+
+    /// RFC 4231, 4.2 - 4.8: (key, data, expected tag)
+    fn rfc4231_vectors() -> Vec<(Vec<u8>, Vec<u8>, &'static str)> {
+        vec![
+            (
+                vec![0x0b; 20],
+                b"Hi There".to_vec(),
+                "b0344c61d8db38535ca8afceaf0bf12b881dc200c9833da726e9376c2e32cff7",
+            ),
+            (
+                b"Jefe".to_vec(),
+                b"what do ya want for nothing?".to_vec(),
+                "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843",
+            ),
+            (
+                vec![0xaa; 20],
+                vec![0xdd; 50],
+                "773ea91e36800e46854db8ebd09181a72959098b3ef8c122d9635514ced565fe",
+            ),
+            (
+                (0x01..=0x19).collect(),
+                vec![0xcd; 50],
+                "82558a389a443c0ea4cc819899f2083a85f0faa3e578f8077a2e3ff46729665b",
+            ),
+            (
+                vec![0xaa; 131],
+                b"Test Using Larger Than Block-Size Key - Hash Key First".to_vec(),
+                "60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54",
+            ),
+            (
+                vec![0xaa; 131],
+                b"This is a test using a larger than block-size key and a larger than block-size \
+                  data. The key needs to be hashed before being used by the HMAC algorithm."
+                    .to_vec(),
+                "9b09ffa71b942fcb27635fbcd5b0e944bfdc63644f0713938a7f51535c3a35e2",
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_hmac_sha256_rfc4231() {
+        for (key, data, expected) in rfc4231_vectors() {
+            assert_eq!(hex::encode(hmac_sha256(&key, &data)), expected);
+        }
+    }
+
+    #[test]
+    fn test_hmac_sha256_truncated_rfc4231() {
+        // RFC 4231, 4.6: output truncated to 128 bits
+        let tag = hmac_sha256(&[0x0c; 20], b"Test With Truncation");
+        assert_eq!(hex::encode(&tag[..16]), "a3b6167473100ee06e0c796c2955552b");
+    }
+
+    #[test]
+    fn test_verify_truncated() {
+        let mac = HmacSha256::new(&[0x0c; 20]);
+        let mut rfc = mac.clone();
+        rfc.update(b"Test With Truncation");
+        let tag = hex::decode("a3b6167473100ee06e0c796c2955552b").unwrap();
+        assert!(bool::from(rfc.clone().verify_truncated(&tag).unwrap()));
+
+        // A flipped bit anywhere in the prefix is rejected
+        for i in 0..tag.len() * 8 {
+            let mut flipped = tag.clone();
+            flipped[i / 8] ^= 1 << (i % 8);
+            assert!(!bool::from(rfc.clone().verify_truncated(&flipped).unwrap()));
+        }
+
+        let full = rfc.clone().finalize();
+        assert!(bool::from(rfc.clone().verify_truncated(&full).unwrap()));
+        assert!(bool::from(rfc.clone().verify_truncated(&full[..8]).unwrap()));
+        assert!(!bool::from(mac.verify_truncated(&tag).unwrap()));
+
+        assert_eq!(rfc.clone().verify_truncated(&full[..7]).unwrap_err(), HmacError::TagTooShort {
+            len: 7,
+            min: 8,
+        });
+        assert_eq!(rfc.clone().verify_truncated(&[0; 33]).unwrap_err(), HmacError::TagTooLong(33));
+        assert_eq!(
+            rfc.clone().verify_truncated_min(&tag[..12], 16).unwrap_err(),
+            HmacError::TagTooShort { len: 12, min: 16 }
+        );
+        assert!(bool::from(rfc.verify_truncated_min(&tag, 16).unwrap()));
+        assert_eq!(
+            HmacError::TagTooShort { len: 7, min: 8 }.to_string(),
+            "HMAC tag length 7 is below minimum 8"
+        );
+    }
+
+    #[test]
+    fn test_hmac_sha256_incremental() {
+        for (key, data, expected) in rfc4231_vectors() {
+            let mut mac = HmacSha256::new(&key);
+            for chunk in data.chunks(7) {
+                mac.update(chunk);
+            }
+            assert_eq!(hex::encode(mac.finalize()), expected);
+        }
+    }
+
+    #[test]
+    fn test_hmac_sha256_block_sized_key() {
+        // A key of exactly one block is used as-is, one byte more is hashed first
+        let key = [0x42u8; BLOCK_SIZE + 1];
+        assert_ne!(hmac_sha256(&key[..BLOCK_SIZE], b"data"), hmac_sha256(&key, b"data"));
+        assert_eq!(hmac_sha256(&key, b"data"), hmac_sha256(&sha256(&key), b"data"));
+    }
+
+    #[test]
+    fn test_hmac_sha256_reset() {
+        let key = b"server key";
+        let mut mac = HmacSha256::new(key);
+        for i in 0..10 {
+            let message = format!("message {}", i).into_bytes();
+            let tag = hmac_sha256(key, &message);
+
+            // Whatever was absorbed before a reset is forgotten
+            mac.update(b"discarded");
+            mac.reset();
+
+            mac.update(&message);
+            assert!(bool::from(mac.clone().verify(&tag)), "Message {} failed", i);
+            let mut wrong = tag;
+            wrong[0] ^= 1;
+            assert!(!bool::from(mac.clone().verify(&wrong)));
+            assert_eq!(mac.finalize_reset(), tag);
+        }
+    }
+
+    #[test]
+    fn test_mac_trait() {
+        fn generic_tag<M: Mac>(key: &[u8], message: &[u8]) -> M::Tag {
+            let mut mac = M::new(key);
+            mac.update(message);
+            mac.finalize()
+        }
+
+        let mut concrete = HmacSha256::new(b"key");
+        let mut object: Box<dyn Mac<Tag = [u8; 32]>> = Box::new(HmacSha256::new(b"key"));
+        for message in [&b""[..], b"Hello, world!", &[0xaa; 200]] {
+            let tag = hmac_sha256(b"key", message);
+            assert_eq!(generic_tag::<HmacSha256>(b"key", message), tag);
+
+            concrete.update(message);
+            object.update(message);
+            assert_eq!(Mac::finalize_reset(&mut concrete), tag);
+            assert_eq!(object.finalize_reset(), tag);
+
+            // Constant-time verification, including tags of the wrong length
+            object.update(message);
+            assert!(bool::from(object.verify_reset(&tag)));
+            object.update(message);
+            assert!(!bool::from(object.verify_reset(&tag[..31])));
+            let mut mac = <HmacSha256 as Mac>::new(b"key");
+            mac.update(message);
+            assert!(!bool::from(Mac::verify(mac, &[0u8; 32])));
+        }
+    }
+}
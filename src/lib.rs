@@ -1 +1,20 @@
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+#[cfg(feature = "alloc")] extern crate alloc;
+
+#[cfg(feature = "rand")] pub mod challenge;
+pub mod commit;
+mod encoding;
+pub mod error;
+#[cfg(feature = "alloc")] pub mod extension;
+pub mod hash;
+pub mod hkdf;
+pub mod hmac;
+pub mod mac;
+#[cfg(feature = "alloc")] pub mod merkle;
+pub mod pow;
 pub mod sha256;
+mod sha2_core;
+pub mod sha512;
+pub mod tagged;
+#[cfg(test)] mod test_rng;
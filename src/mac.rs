@@ -0,0 +1,94 @@
+//! Common interface of message authentication codes.
+//!
+//! Code written against [`Mac`] works with any MAC in this crate, currently [`HmacSha256`], and
+//! will work unchanged with wider ones added later through the [`Mac::Tag`] type.
+//!
+//! # Examples
+//!
+//! ```
+//! use shs_rs::{hmac::HmacSha256, mac::Mac};
+//!
+//! fn authenticate<M: Mac>(key: &[u8], message: &[u8]) -> M::Tag {
+//!     let mut mac = M::new(key);
+//!     mac.update(message);
+//!     mac.finalize()
+//! }
+//!
+//! let tag = authenticate::<HmacSha256>(b"key", b"Hello, world!");
+//! assert_eq!(tag, shs_rs::hmac::hmac_sha256(b"key", b"Hello, world!"));
+//! ```
+//!
+//! [`HmacSha256`]: crate::hmac::HmacSha256
+
+use subtle::{Choice, ConstantTimeEq};
+
+/// A keyed message authentication code.
+///
+/// Usable as a trait object, e.g. `Box<dyn Mac<Tag = [u8; 32]>>`, through the methods that don't
+/// consume the MAC.
+pub trait Mac {
+    /// Authentication tag, `[u8; 32]` for HMAC-SHA256.
+    type Tag: AsRef<[u8]>;
+
+    /// Create a MAC keyed with `key`.
+    ///
+    /// # Parameters
+    ///
+    /// - `key`: Secret key of any length.
+    fn new(key: &[u8]) -> Self
+    where Self: Sized;
+
+    /// Absorb more of the message.
+    ///
+    /// # Parameters
+    ///
+    /// - `data`: Next piece of the message.
+    fn update(&mut self, data: &[u8]);
+
+    /// Compute the authentication tag and reset for the next message, keeping the key.
+    ///
+    /// # Returns
+    ///
+    /// Tag over all data passed to [`Mac::update`] since the last reset.
+    #[must_use]
+    fn finalize_reset(&mut self) -> Self::Tag;
+
+    /// Compute the authentication tag.
+    ///
+    /// # Returns
+    ///
+    /// Tag over all data passed to [`Mac::update`].
+    #[must_use]
+    fn finalize(mut self) -> Self::Tag
+    where Self: Sized {
+        self.finalize_reset()
+    }
+
+    /// Compute the authentication tag, compare it to an expected one in constant time, and reset
+    /// for the next message.
+    ///
+    /// # Parameters
+    ///
+    /// - `tag`: Tag received with the message.
+    ///
+    /// # Returns
+    ///
+    /// [`Choice`] holding `1` if `tag` is valid for the absorbed message and `0` otherwise.
+    #[must_use]
+    fn verify_reset(&mut self, tag: &[u8]) -> Choice { self.finalize_reset().as_ref().ct_eq(tag) }
+
+    /// Compute the authentication tag and compare it to an expected one in constant time.
+    ///
+    /// # Parameters
+    ///
+    /// - `tag`: Tag received with the message.
+    ///
+    /// # Returns
+    ///
+    /// [`Choice`] holding `1` if `tag` is valid for the absorbed message and `0` otherwise.
+    #[must_use]
+    fn verify(mut self, tag: &[u8]) -> Choice
+    where Self: Sized {
+        self.verify_reset(tag)
+    }
+}
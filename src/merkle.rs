@@ -0,0 +1,298 @@
+//! Binary Merkle trees over SHA-256 based on RFC 6962.
+//!
+//! Leaves and internal nodes are hashed with distinct prefixes, so a node can never be passed
+//! off as a leaf:
+//!
+//! - leaf: `sha256(0x00 || leaf)`
+//! - node: `sha256(0x01 || left || right)`
+//!
+//! The tree is built bottom-up, pairing nodes from the left. A lone node at the end of a level is
+//! promoted to the next level unchanged rather than duplicated, which yields the same root as the
+//! Merkle Tree Hash of RFC 6962, 2.1.
+//!
+//! # References
+//!
+//! - [RFC 6962: Certificate Transparency](https://www.rfc-editor.org/rfc/rfc6962)
+//!
+//! # Examples
+//!
+//! ```
+//! use shs_rs::merkle::{verify_proof, MerkleTree};
+//!
+//! let leaves: [&[u8]; 3] = [b"a", b"b", b"c"];
+//! let tree = MerkleTree::from_leaves(&leaves);
+//! let proof = tree.proof(1);
+//! assert!(verify_proof(&tree.root(), b"b", 1, &proof));
+//! ```
+
+use alloc::{vec, vec::Vec};
+
+use crate::sha256::{sha256, verify, Sha256};
+
+/// Domain separation prefix of leaf hashes.
+///
+/// See: RFC 6962, 2.1
+const LEAF_PREFIX: u8 = 0x00;
+
+/// Domain separation prefix of internal node hashes.
+///
+/// See: RFC 6962, 2.1
+const NODE_PREFIX: u8 = 0x01;
+
+/// Hash a leaf.
+///
+/// # Parameters
+///
+/// - `leaf`: Leaf data.
+///
+/// # Returns
+///
+/// `sha256(0x00 || leaf)`.
+#[must_use]
+pub fn leaf_hash(leaf: &[u8]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(&[LEAF_PREFIX]);
+    hasher.update(leaf);
+    hasher.finalize()
+}
+
+/// Hash two child nodes into their parent.
+///
+/// # Parameters
+///
+/// - `left`, `right`: Hashes of the child nodes.
+///
+/// # Returns
+///
+/// `sha256(0x01 || left || right)`.
+#[must_use]
+pub fn node_hash(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(&[NODE_PREFIX]);
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finalize()
+}
+
+/// Merkle tree with all intermediate levels kept for proof generation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MerkleTree {
+    /// Hashes of each level, from the leaves up to the root.
+    levels: Vec<Vec<[u8; 32]>>,
+}
+
+impl MerkleTree {
+    /// Build a tree over `leaves`.
+    ///
+    /// # Parameters
+    ///
+    /// - `leaves`: Leaf data, in order.
+    pub fn from_leaves(leaves: &[&[u8]]) -> Self {
+        let mut levels = vec![leaves.iter().map(|leaf| leaf_hash(leaf)).collect::<Vec<_>>()];
+        while let Some(level) = levels.last().filter(|level| level.len() > 1) {
+            let next = level
+                .chunks(2)
+                .map(|pair| match pair {
+                    [left, right] => node_hash(left, right),
+                    // Promote the lone node
+                    [lone] => *lone,
+                    _ => unreachable!(),
+                })
+                .collect();
+            levels.push(next);
+        }
+        Self { levels }
+    }
+
+    /// Number of leaves in the tree.
+    pub fn len(&self) -> usize { self.levels[0].len() }
+
+    /// Whether the tree has no leaves.
+    pub fn is_empty(&self) -> bool { self.len() == 0 }
+
+    /// Root hash of the tree.
+    ///
+    /// See: RFC 6962, 2.1
+    ///
+    /// # Returns
+    ///
+    /// The root node, or `sha256("")` for a tree without leaves.
+    #[must_use]
+    pub fn root(&self) -> [u8; 32] {
+        match self.levels.last().and_then(|level| level.first()) {
+            Some(root) => *root,
+            None => sha256(&[]),
+        }
+    }
+
+    /// Inclusion proof of a leaf.
+    ///
+    /// # Parameters
+    ///
+    /// - `index`: Position of the leaf.
+    ///
+    /// # Returns
+    ///
+    /// Sibling hashes from the leaf level up to just below the root. Levels at which the node was
+    /// promoted have no sibling and contribute nothing.
+    ///
+    /// # Panics
+    ///
+    /// If `index` is not less than [`MerkleTree::len`].
+    pub fn proof(&self, index: usize) -> Vec<[u8; 32]> {
+        assert!(index < self.len(), "leaf index {} out of range", index);
+
+        let mut proof = Vec::new();
+        let mut i = index;
+        for level in &self.levels[..self.levels.len() - 1] {
+            if let Some(sibling) = level.get(i ^ 1) {
+                proof.push(*sibling);
+            }
+            i >>= 1;
+        }
+        proof
+    }
+}
+
+/// Verify an inclusion proof produced by [`MerkleTree::proof`].
+///
+/// The size of the tree is not needed: a node at an odd index always has a left sibling, while a
+/// node at an even index has a right sibling unless it was promoted. A promoted node is the last
+/// of its level and of every level above, so from there on only the left siblings given by the
+/// set bits of its index remain in the proof.
+///
+/// Only the leaf and the siblings are hashed, so light clients can check inclusion without the
+/// tree. The recomputed root is compared to `root` in constant time.
+///
+/// # Parameters
+///
+/// - `root`: Expected root hash.
+/// - `leaf`: Leaf data.
+/// - `index`: Claimed position of the leaf.
+/// - `proof`: Sibling hashes, see [`MerkleTree::proof`].
+///
+/// # Returns
+///
+/// `true` if `leaf` is included in the tree with root `root` at position `index`, and `false`
+/// otherwise, including when `index` is out of range for a proof of this length.
+#[must_use]
+pub fn verify_proof(root: &[u8; 32], leaf: &[u8], index: usize, proof: &[[u8; 32]]) -> bool {
+    let mut hash = leaf_hash(leaf);
+    let mut siblings = proof.iter();
+    let mut i = index;
+    while i > 0 || siblings.len() > 0 {
+        if i & 1 == 1 {
+            match siblings.next() {
+                Some(left) => hash = node_hash(left, &hash),
+                None => return false,
+            }
+        } else if siblings.len() > (i >> 1).count_ones() as usize {
+            hash = node_hash(&hash, siblings.next().unwrap());
+        }
+        i >>= 1;
+    }
+    verify(root, &hash).into()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Merkle Tree Hash as defined recursively in RFC 6962, 2.1
+    fn rfc6962_root(leaves: &[&[u8]]) -> [u8; 32] {
+        match leaves.len() {
+            0 => sha256(&[]),
+            1 => leaf_hash(leaves[0]),
+            n => {
+                let k = n.next_power_of_two() / 2;
+                node_hash(&rfc6962_root(&leaves[..k]), &rfc6962_root(&leaves[k..]))
+            },
+        }
+    }
+
+    fn leaves(n: usize) -> Vec<Vec<u8>> {
+        (0..n).map(|i| format!("leaf {}", i).into_bytes()).collect()
+    }
+
+    #[test]
+    fn test_root_matches_rfc6962() {
+        for n in 0..=17 {
+            let leaves = leaves(n);
+            let leaves: Vec<&[u8]> = leaves.iter().map(Vec::as_slice).collect();
+            assert_eq!(
+                MerkleTree::from_leaves(&leaves).root(),
+                rfc6962_root(&leaves),
+                "{} leaves",
+                n
+            );
+        }
+    }
+
+    #[test]
+    fn test_domain_separation() {
+        let (a, b) = (leaf_hash(b"a"), leaf_hash(b"b"));
+        assert_ne!(leaf_hash(b""), sha256(b""));
+
+        // An internal node can't be passed off as a leaf
+        let mut forged = vec![NODE_PREFIX];
+        forged.extend_from_slice(&a);
+        forged.extend_from_slice(&b);
+        assert_eq!(node_hash(&a, &b), sha256(&forged));
+        assert_ne!(leaf_hash(&forged[1..]), node_hash(&a, &b));
+    }
+
+    #[test]
+    fn test_proofs_five_leaves() {
+        let leaves = leaves(5);
+        let leaves: Vec<&[u8]> = leaves.iter().map(Vec::as_slice).collect();
+        let tree = MerkleTree::from_leaves(&leaves);
+        let root = tree.root();
+
+        for (index, leaf) in leaves.iter().enumerate() {
+            let proof = tree.proof(index);
+            assert!(verify_proof(&root, leaf, index, &proof), "Leaf {} failed", index);
+
+            // Wrong leaf, position, or proof
+            assert!(!verify_proof(&root, b"other", index, &proof));
+            assert!(!verify_proof(&root, leaf, (index + 1) % 5, &proof));
+            assert!(!verify_proof(&root, leaf, index, &proof[1..]));
+            let mut tampered = proof.clone();
+            tampered[0][0] ^= 1;
+            assert!(!verify_proof(&root, leaf, index, &tampered));
+        }
+
+        // The fifth leaf is promoted twice, its only sibling is the root of the first four
+        assert_eq!(tree.proof(4), vec![rfc6962_root(&leaves[..4])]);
+    }
+
+    #[test]
+    fn test_proofs_all_sizes() {
+        for n in 1..=17 {
+            let leaves = leaves(n);
+            let leaves: Vec<&[u8]> = leaves.iter().map(Vec::as_slice).collect();
+            let tree = MerkleTree::from_leaves(&leaves);
+            for (index, leaf) in leaves.iter().enumerate() {
+                assert!(verify_proof(&tree.root(), leaf, index, &tree.proof(index)));
+            }
+        }
+    }
+
+    #[test]
+    fn test_verify_proof_index_out_of_range() {
+        let leaves = leaves(4);
+        let leaves: Vec<&[u8]> = leaves.iter().map(Vec::as_slice).collect();
+        let tree = MerkleTree::from_leaves(&leaves);
+        let proof = tree.proof(3);
+
+        // Indices needing more siblings than the proof holds are rejected without panicking
+        for index in [4, 7, 8, 1 << 40, usize::MAX] {
+            assert!(!verify_proof(&tree.root(), leaves[3], index, &proof));
+        }
+        assert!(!verify_proof(&tree.root(), leaves[0], usize::MAX, &[]));
+        assert!(verify_proof(&leaf_hash(leaves[0]), leaves[0], 0, &[]));
+    }
+
+    #[test]
+    #[should_panic(expected = "out of range")]
+    fn test_proof_out_of_range() { MerkleTree::from_leaves(&[b"a"]).proof(1); }
+}
@@ -0,0 +1,135 @@
+//! Hashcash-style proof of work.
+//!
+//! A solution for `prefix` is a nonce such that `sha256(prefix || nonce)`, with the nonce appended
+//! as eight big-endian bytes, starts with at least `difficulty_bits` zero bits. Finding one takes
+//! about `2^difficulty_bits` hashes, checking it takes one.
+//!
+//! # Examples
+//!
+//! ```
+//! use shs_rs::{pow::mine, sha256::Digest};
+//!
+//! let (nonce, digest) = mine(b"block header", 8);
+//! assert!(Digest(digest).leading_zeros() >= 8);
+//! ```
+
+use crate::sha256::{Digest, Sha256};
+
+/// Largest supported difficulty, every bit of the digest being zero.
+pub const MAX_DIFFICULTY: u32 = 256;
+
+/// Hash `prefix || nonce` from a hasher that has already absorbed `prefix`.
+fn hash_nonce(midstate: &Sha256, nonce: u64) -> [u8; 32] {
+    let mut hasher = midstate.clone();
+    hasher.update(&nonce.to_be_bytes());
+    hasher.finalize()
+}
+
+/// Hasher that has absorbed `prefix`, shared by every nonce tried.
+fn midstate(prefix: &[u8], difficulty_bits: u32) -> Sha256 {
+    assert!(difficulty_bits <= MAX_DIFFICULTY, "difficulty can't exceed 256 bits");
+    let mut hasher = Sha256::new();
+    hasher.update(prefix);
+    hasher
+}
+
+/// Search for the smallest nonce solving the proof of work.
+///
+/// # Parameters
+///
+/// - `prefix`: Data the work is bound to.
+/// - `difficulty_bits`: Required number of leading zero bits, at most [`MAX_DIFFICULTY`].
+///
+/// # Returns
+///
+/// The nonce and `sha256(prefix || nonce)`.
+///
+/// # Panics
+///
+/// If `difficulty_bits` exceeds [`MAX_DIFFICULTY`], or no `u64` nonce solves it.
+#[must_use]
+pub fn mine(prefix: &[u8], difficulty_bits: u32) -> (u64, [u8; 32]) {
+    let midstate = midstate(prefix, difficulty_bits);
+    (0..=u64::MAX)
+        .map(|nonce| (nonce, hash_nonce(&midstate, nonce)))
+        .find(|(_, digest)| Digest(*digest).leading_zeros() >= difficulty_bits)
+        .expect("no nonce solves the proof of work")
+}
+
+/// Search for a nonce solving the proof of work on all threads.
+///
+/// The nonce space is split across the rayon thread pool and the first solution any thread finds
+/// is returned, which is not necessarily the smallest one [`mine`] would find.
+///
+/// # Parameters
+///
+/// - `prefix`: Data the work is bound to.
+/// - `difficulty_bits`: Required number of leading zero bits, at most [`MAX_DIFFICULTY`].
+///
+/// # Returns
+///
+/// The nonce and `sha256(prefix || nonce)`.
+///
+/// # Panics
+///
+/// If `difficulty_bits` exceeds [`MAX_DIFFICULTY`], or no `u64` nonce solves it.
+#[cfg(feature = "rayon")]
+#[must_use]
+pub fn mine_parallel(prefix: &[u8], difficulty_bits: u32) -> (u64, [u8; 32]) {
+    use rayon::prelude::*;
+
+    let midstate = midstate(prefix, difficulty_bits);
+    (0..=u64::MAX)
+        .into_par_iter()
+        .map(|nonce| (nonce, hash_nonce(&midstate, nonce)))
+        .find_any(|(_, digest)| Digest(*digest).leading_zeros() >= difficulty_bits)
+        .expect("no nonce solves the proof of work")
+}
+
+/// Check a proof-of-work solution.
+///
+/// # Parameters
+///
+/// - `prefix`: Data the work is bound to.
+/// - `nonce`: Claimed solution.
+/// - `difficulty_bits`: Required number of leading zero bits.
+///
+/// # Returns
+///
+/// Whether `sha256(prefix || nonce)` has at least `difficulty_bits` leading zero bits.
+#[must_use]
+pub fn verify_work(prefix: &[u8], nonce: u64, difficulty_bits: u32) -> bool {
+    let mut hasher = Sha256::new();
+    hasher.update(prefix);
+    Digest(hash_nonce(&hasher, nonce)).leading_zeros() >= difficulty_bits
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::sha256::sha256;
+
+    #[test]
+    fn test_mine() {
+        let (nonce, digest) = mine(b"shs-rs", 8);
+        assert_eq!(digest, sha256(&[&b"shs-rs"[..], &nonce.to_be_bytes()].concat()));
+        assert!(Digest(digest).leading_zeros() >= 8);
+        assert!(verify_work(b"shs-rs", nonce, 8));
+
+        // No smaller nonce works
+        assert!((0..nonce).all(|smaller| !verify_work(b"shs-rs", smaller, 8)));
+        assert_eq!(mine(b"shs-rs", 0).0, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "difficulty can't exceed 256 bits")]
+    fn test_mine_impossible_difficulty() { let _ = mine(b"", 257); }
+
+    #[test]
+    #[cfg(feature = "rayon")]
+    fn test_mine_parallel() {
+        let (nonce, digest) = mine_parallel(b"shs-rs", 8);
+        assert_eq!(digest, sha256(&[&b"shs-rs"[..], &nonce.to_be_bytes()].concat()));
+        assert!(verify_work(b"shs-rs", nonce, 8));
+    }
+}
@@ -17,41 +17,62 @@
 //! println!("SHA-256 digest: {:x?}", digest);
 //! ```
 
-use subtle::{Choice, ConditionallySelectable};
+#[cfg(feature = "alloc")]
+use alloc::{string::String, vec::Vec};
+use core::{fmt, ops::Range};
 
-/// Rotate right (circular right shift) operation.
-///
-/// See: FIPS 180-4, 3.2
-///
-/// # Parameters
-///
-/// - `x`: `W`-bit word.
-const fn rotr<const N: u32>(x: u32) -> u32 { x.rotate_right(N) }
-
-/// Shift right operation.
-///
-/// See: FIPS 180-4, 3.2
-///
-/// # Parameters
-///
-/// - `n`: An integer with `0 <= n < 32`.
-const fn shr<const N: u32>(x: u32) -> u32 { x.wrapping_shr(N) }
-
-/// See: FIPS 180-4, 4.1.2
-
-#[inline(always)]
-fn ch(x: u32, y: u32, z: u32) -> u32 { (x & y) ^ (!x & z) }
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq, ConstantTimeLess};
 
-#[inline(always)]
-fn maj(x: u32, y: u32, z: u32) -> u32 { (x & y) ^ (x & z) ^ (y & z) }
-
-const fn csigma0(x: u32) -> u32 { rotr::<2>(x) ^ rotr::<13>(x) ^ rotr::<22>(x) }
+#[cfg(feature = "alloc")] use crate::encoding::encode_hex;
+use crate::{
+    error::ShaError,
+    hash::{HashFunction, OutputSize},
+    sha2_core::sha2_core,
+};
 
-const fn csigma1(x: u32) -> u32 { rotr::<6>(x) ^ rotr::<11>(x) ^ rotr::<25>(x) }
+#[cfg(target_arch = "aarch64")] mod aarch64;
+#[cfg(feature = "async")] mod async_io;
+mod backend;
+#[cfg(feature = "serde")] pub mod digest_codec;
+mod fixed_time;
+#[cfg(feature = "std")] mod io;
+mod multi;
+mod output;
+mod params;
+mod prefix;
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+mod x86;
 
-const fn sigma0(x: u32) -> u32 { rotr::<7>(x) ^ rotr::<18>(x) ^ shr::<3>(x) }
+#[cfg(feature = "async")]
+pub use async_io::AsyncHashingWriter;
+pub use backend::{
+    active_backend, compute_hash_with_backend, set_backend_override, Backend, UnsupportedBackend,
+};
+pub use fixed_time::sha256_fixed_time;
+#[cfg(feature = "std")]
+pub use io::{
+    profile, sha256_file, sha256_file_with_capacity, sha256_reader, sha256_reader_with_capacity,
+    sha256_try_chunks, HashingReader, HashingWriter, ProfilingWriter,
+};
+pub use multi::sha256_x4;
+pub use output::{fold_xor, Digest, ParseDigestError, PublicDigest};
+pub use params::{sha256_with_params, Sha256Params};
+pub use prefix::PrefixHasher;
 
-const fn sigma1(x: u32) -> u32 { rotr::<17>(x) ^ rotr::<19>(x) ^ shr::<10>(x) }
+// Logical functions, padding, message schedule and rounds with the SHA-256 parameters.
+//
+// See: FIPS 180-4, 4.1.2, 5.1.1 and 6.2.2
+sha2_core! {
+    word: u32,
+    length: u64,
+    block_size: BLOCK_SIZE,
+    rounds: 64,
+    k: WORDS_K,
+    csigma0: (2, 13, 22),
+    csigma1: (6, 11, 25),
+    sigma0: (7, 18, 3),
+    sigma1: (17, 19, 10),
+}
 
 /// `WORDS_K`, also known as "round constants",  represent the first thirty-two bits of the
 /// fractional parts of the cube roots of the first sixty-four prime numbers.
@@ -68,6 +89,29 @@ const WORDS_K: [u32; 64] = [
     0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
 ];
 
+/// [`WORDS_K`] in groups of four, one 128-bit SIMD register per four rounds.
+///
+/// The SHA extensions on x86 and ARMv8 run four rounds per round constant register, so each row
+/// can be loaded as is, without indexing into the flat table.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64", target_arch = "aarch64"))]
+const WORDS_K_GROUPED: [[u32; 4]; 16] = group_words_k();
+
+/// Split [`WORDS_K`] into rows of four consecutive constants.
+///
+/// # Returns
+///
+/// Rows `[K_4g, K_4g+1, K_4g+2, K_4g+3]` for `g` in `0..16`.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64", target_arch = "aarch64"))]
+const fn group_words_k() -> [[u32; 4]; 16] {
+    let mut grouped = [[0; 4]; 16];
+    let mut t = 0;
+    while t < 64 {
+        grouped[t / 4][t % 4] = WORDS_K[t];
+        t += 1;
+    }
+    grouped
+}
+
 /// Pad a message into a multiple of 512 bits.
 ///
 /// See: FIPS 180-4, 5.1.1
@@ -78,49 +122,41 @@ const WORDS_K: [u32; 64] = [
 ///
 /// # Returns
 ///
-/// A padded message ready to be transformed.
-fn padding(message: &[u8]) -> Vec<u8> {
-    // Pre-allocate the maximum possible size to avoid potential timing attacks based on allocation
-    // Maximum padding (512 bits) + 64-bit length
-    let l_bits = message.len() * 8;
-    let max_padding = 64 + 8;
-    let max_len = message.len() + max_padding;
-    let mut padded = vec![0u8; max_len];
-
-    // Copy message to padded vector in constant time
-    for (i, &byte) in message.iter().enumerate() {
-        padded[i] = byte;
-    }
-
-    // Append "1" bit to the end of message
-    padded[message.len()] = 0x80;
-
-    // Calculate k bits in constant time
-    // We want: (l_bits + 1 + k) % 512 = 448
-    // So: k = (448 - (l_bits + 1) % 512) % 512
-    // But we need to handle the case where l_bits + 1 > 448
-    let k = {
-        let mut k = 0u32;
-        for i in 0..512u32 {
-            let condition =
-                Choice::from(((448 + 512 - (l_bits as u32 + 1 + i) % 512) % 512 == 0) as u8);
-            k = u32::conditional_select(&k, &i, condition);
-        }
-        k / 8
-    };
+/// A padded message ready to be transformed. See [`padding_into`] for an allocation-free variant.
+#[cfg(feature = "alloc")]
+pub fn padding(message: &[u8]) -> Vec<u8> {
+    let (tail, tail_blocks) = final_blocks(message);
+    let full_len = message.len() - message.len() % BLOCK_SIZE;
 
-    // Append length as 64-bit big-endian integer
-    let length_bytes = (l_bits as u64).to_be_bytes();
-    for i in 0..8 {
-        padded[message.len() + (k as usize) + 1 + i] = length_bytes[i];
-    }
-
-    // Truncate to the actual padded length
-    padded.truncate(message.len() + (k as usize) + 9);
+    let mut padded = Vec::with_capacity(full_len + tail_blocks * BLOCK_SIZE);
+    padded.extend_from_slice(&message[..full_len]);
+    padded.extend_from_slice(&tail[..tail_blocks * BLOCK_SIZE]);
 
+    debug_assert_eq!(padded.len() % BLOCK_SIZE, 0);
     padded
 }
 
+/// Pad the final partial block of a message into a caller-provided buffer.
+///
+/// Only the trailing `message.len() % 64` bytes are copied; complete blocks preceding them can
+/// be processed straight from `message`.
+///
+/// See: FIPS 180-4, 5.1.1
+///
+/// # Parameters
+///
+/// - `message`: The whole message, used for its final partial block and total length.
+/// - `out`: Buffer receiving the padded final block(s).
+///
+/// # Returns
+///
+/// The number of valid 512-bit blocks written to `out`, either 1 or 2.
+pub fn padding_into(message: &[u8], out: &mut [u8; 128]) -> usize {
+    let tail_blocks;
+    (*out, tail_blocks) = final_blocks(message);
+    tail_blocks
+}
+
 /// Initial hash value.
 ///
 /// See: FIPS 180-4, 5.3.3
@@ -139,82 +175,209 @@ pub const IHV: [u32; 8] = [
 /// # Returns
 ///
 /// A 256-bit digest of `blocks`.
+///
+/// # Panics
+///
+/// If any of `blocks` is not exactly [`BLOCK_SIZE`] bytes long.
+#[must_use]
 pub fn compute_hash(initial_state: [u32; 8], blocks: &[&[u8]]) -> [u8; 32] {
     // SHA-256 Preprocessing
     let mut hash_value = initial_state;
 
     // Process every message block M_i
-    for block in blocks.iter() {
-        let mut w = [0u32; 64];
-
-        // Prepare message schedule
-        for t in 0..16 {
-            // Divide a 512-bit block into sixteen 32-bit words
-            // See: FIPS 180-4, 6.2.2
-            w[t] = u32::from_be_bytes([
-                block[4 * t],
-                block[4 * t + 1],
-                block[4 * t + 2],
-                block[4 * t + 3],
-            ]);
-        }
-        // Remaining 48 words
-        for t in 16..64 {
-            w[t] = sigma1(w[t - 2])
-                .wrapping_add(w[t - 7])
-                .wrapping_add(sigma0(w[t - 15]))
-                .wrapping_add(w[t - 16]);
-        }
-
-        // Hash computation
-        let (mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h) = (
-            hash_value[0],
-            hash_value[1],
-            hash_value[2],
-            hash_value[3],
-            hash_value[4],
-            hash_value[5],
-            hash_value[6],
-            hash_value[7],
+    for (index, &block) in blocks.iter().enumerate() {
+        compress(&mut hash_value, block.try_into().expect("message blocks must be 64 bytes"));
+        trace_progress("compute_hash", index as u64 * BLOCK_SIZE as u64, BLOCK_SIZE as u64);
+    }
+
+    state_to_bytes(&hash_value)
+}
+
+/// Number of blocks between two progress events of the `tracing` feature, 64 KiB of input.
+#[cfg(feature = "tracing")]
+const TRACE_INTERVAL_BLOCKS: u64 = 1024;
+
+/// Emit a trace event if absorbing `len` more bytes completes another
+/// [`TRACE_INTERVAL_BLOCKS`] blocks.
+///
+/// # Parameters
+///
+/// - `source`: Name of the hashing entry point, recorded with the event.
+/// - `processed`: Bytes absorbed before this piece.
+/// - `len`: Length of this piece.
+#[cfg(feature = "tracing")]
+fn trace_progress(source: &'static str, processed: u64, len: u64) {
+    const INTERVAL_BYTES: u64 = TRACE_INTERVAL_BLOCKS * BLOCK_SIZE as u64;
+    let total = processed + len;
+    if total / INTERVAL_BYTES > processed / INTERVAL_BYTES {
+        tracing::trace!(
+            source,
+            blocks = total / BLOCK_SIZE as u64,
+            bytes = total,
+            "sha256 progress"
         );
+    }
+}
+
+/// Without the `tracing` feature, progress is not reported and the calls compile to nothing.
+#[cfg(not(feature = "tracing"))]
+#[inline(always)]
+fn trace_progress(_source: &'static str, _processed: u64, _len: u64) {}
+
+/// SHA-256 Hash Computation, checking the block lengths instead of panicking.
+///
+/// See: FIPS 180-4, 6.2.2
+///
+/// # Parameters
+///
+/// - `initial_state`: Hash value to start from, usually [`IHV`].
+/// - `blocks`: A message to compute digest over, already divided into 512-bit blocks.
+///
+/// # Returns
+///
+/// A 256-bit digest of `blocks`, or [`ShaError::InvalidBlockLength`] naming the first block that
+/// is not exactly [`BLOCK_SIZE`] bytes long.
+///
+/// # Examples
+///
+/// ```
+/// use shs_rs::{
+///     error::ShaError,
+///     sha256::{try_compute_hash, IHV},
+/// };
+///
+/// let err = try_compute_hash(IHV, &[&[0u8; 64], &[0u8; 63]]).unwrap_err();
+/// assert_eq!(err, ShaError::InvalidBlockLength { index: 1, len: 63 });
+/// ```
+pub fn try_compute_hash(initial_state: [u32; 8], blocks: &[&[u8]]) -> Result<[u8; 32], ShaError> {
+    if let Some((index, block)) =
+        blocks.iter().enumerate().find(|(_, block)| block.len() != BLOCK_SIZE)
+    {
+        return Err(ShaError::InvalidBlockLength { index, len: block.len() });
+    }
+    Ok(compute_hash(initial_state, blocks))
+}
+
+/// SHA-256 compression function, processing a single 512-bit block.
+///
+/// This is the building block of [`compute_hash`]; no padding or length encoding is applied.
+///
+/// See: FIPS 180-4, 6.2.2
+///
+/// # Parameters
+///
+/// - `state`: Intermediate hash value `H^(i-1)`, e.g. [`IHV`], replaced with `H^(i)`.
+/// - `block`: 512-bit message block `M^(i)`.
+///
+/// # Examples
+///
+/// ```
+/// use shs_rs::sha256::{compress, IHV};
+///
+/// let mut state = IHV;
+/// compress(&mut state, &[0u8; 64]);
+/// ```
+pub fn compress(state: &mut [u32; 8], block: &[u8; BLOCK_SIZE]) { compress_blocks(state, block); }
+
+/// Expand a 512-bit block into the message schedule, without running the compression rounds.
+///
+/// Useful for checking intermediate values against a reference implementation.
+///
+/// See: FIPS 180-4, 6.2.2
+///
+/// # Parameters
+///
+/// - `block`: 512-bit message block `M^(i)`.
+///
+/// # Returns
+///
+/// The sixty-four words `W_0..W_63`: the block read as sixteen big-endian words, followed by the
+/// forty-eight words of the recurrence.
+///
+/// # Examples
+///
+/// ```
+/// use shs_rs::sha256::message_schedule;
+///
+/// let w = message_schedule(&[0u8; 64]);
+/// assert_eq!(w, [0u32; 64]);
+/// ```
+#[must_use]
+pub fn message_schedule(block: &[u8; BLOCK_SIZE]) -> [u32; 64] { schedule(block) }
+
+/// SHA-256 Hash Computation over contiguous blocks.
+///
+/// Equivalent to [`compute_hash`] without having to split `data` into a slice of blocks first.
+///
+/// See: FIPS 180-4, 6.2.2
+///
+/// # Parameters
+///
+/// - `initial_state`: Hash value to start from, usually [`IHV`].
+/// - `data`: A message to compute digest over, already padded to a multiple of 512 bits.
+///
+/// # Returns
+///
+/// A 256-bit digest of `data`.
+///
+/// # Panics
+///
+/// If `data.len()` is not a multiple of [`BLOCK_SIZE`].
+#[must_use]
+pub fn compute_hash_bytes(initial_state: [u32; 8], data: &[u8]) -> [u8; 32] {
+    assert_eq!(data.len() % BLOCK_SIZE, 0, "data must consist of complete 512-bit blocks");
+
+    let mut hash_value = initial_state;
+    compress_blocks(&mut hash_value, data);
+    state_to_bytes(&hash_value)
+}
+
+/// Process every complete 512-bit block of `data`, ignoring any trailing partial block.
+///
+/// Uses the fastest backend supported by the CPU, all of which produce identical results: on x86
+/// the SHA extensions, then AVX2, then SSE2, and on aarch64 the SHA2 extension, falling back to
+/// portable code. [`set_backend_override`] takes precedence.
+///
+/// # Parameters
+///
+/// - `hash_value`: Intermediate hash value, updated in place.
+/// - `data`: Message blocks `M^(1)..M^(N)`.
+fn compress_blocks(hash_value: &mut [u32; 8], data: &[u8]) {
+    let backend = backend::active_backend();
+    // SAFETY: the selected backend is always available
+    unsafe { backend.compress_blocks(hash_value, data) }
+}
 
-        let mut temp_1;
-        let mut temp_2;
-        for t in 0..64 {
-            temp_1 = h
-                .wrapping_add(csigma1(e))
-                .wrapping_add(ch(e, f, g))
-                .wrapping_add(WORDS_K[t])
-                .wrapping_add(w[t]);
-            temp_2 = csigma0(a).wrapping_add(maj(a, b, c));
-            h = g;
-            g = f;
-            f = e;
-            e = d.wrapping_add(temp_1);
-            d = c;
-            c = b;
-            b = a;
-            a = temp_1.wrapping_add(temp_2);
-        }
-
-        // Compute intermediate hash values
-        hash_value = hash_value
-            .iter()
-            .zip([a, b, c, d, e, f, g, h].iter())
-            .map(|(&x, &y)| x.wrapping_add(y))
-            .collect::<Vec<_>>()
-            .try_into()
-            .unwrap();
+/// Portable fallback of [`compress_blocks`].
+fn compress_blocks_portable(hash_value: &mut [u32; 8], data: &[u8]) {
+    for block in blocks(data) {
+        compress_block(hash_value, block);
     }
+}
 
-    // Final digest
-    let mut result = [0u8; 32];
-    for (i, &word) in hash_value.iter().enumerate() {
-        result[i * 4..(i + 1) * 4].copy_from_slice(&word.to_be_bytes());
+/// Parse a big-endian digest back into the hash value it serializes, see [`state_to_bytes`].
+pub(crate) fn bytes_to_state(digest: &[u8; 32]) -> [u32; 8] {
+    let mut hash_value = [0u32; 8];
+    for (word, bytes) in hash_value.iter_mut().zip(digest.chunks_exact(4)) {
+        *word = u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
     }
+    hash_value
+}
+
+/// Serialize the final hash value into a big-endian digest.
+fn state_to_bytes(hash_value: &[u32; 8]) -> [u8; 32] {
+    let mut result = [0u8; 32];
+    state_to_bytes_into(hash_value, &mut result);
     result
 }
 
+/// Serialize the final hash value into a big-endian digest, in place.
+fn state_to_bytes_into(hash_value: &[u32; 8], out: &mut [u8; 32]) {
+    for (bytes, word) in out.chunks_exact_mut(4).zip(hash_value) {
+        bytes.copy_from_slice(&word.to_be_bytes());
+    }
+}
+
 /// Compute SHA-256 digest of a message.
 ///
 /// # Parameters
@@ -233,78 +396,1634 @@ pub fn compute_hash(initial_state: [u32; 8], blocks: &[&[u8]]) -> [u8; 32] {
 /// let digest = sha256(message);
 /// println!("SHA-256 digest: {:x?}", digest);
 /// ```
-pub fn sha256(message: &[u8]) -> [u8; 32] {
-    let padded = padding(message);
-    // Divide the message into 512-bit blocks: FIPS 180-4, 5.2.1
-    let blocks: Vec<&[u8]> = padded.chunks_exact(64).collect();
-    compute_hash(IHV, &blocks)
-}
-
-#[cfg(test)]
-mod test {
-    use super::*;
+#[must_use]
+pub fn sha256(message: &[u8]) -> [u8; 32] { sha256_with_iv(IHV, message) }
 
-    #[test]
-    fn test_rotr() {
-        assert_eq!(rotr::<0>(0x12345678), 0x12345678);
-        assert_eq!(rotr::<4>(0x12345678), 0x81234567);
-        assert_eq!(rotr::<8>(0x12345678), 0x78123456);
-        assert_eq!(rotr::<16>(0x12345678), 0x56781234);
-        assert_eq!(rotr::<24>(0x12345678), 0x34567812);
-        assert_eq!(rotr::<31>(0x12345678), 0x2468acf0);
-    }
+/// Compute SHA-256 digest of a message into an existing buffer.
+///
+/// # Parameters
+///
+/// - `message`: Input message to hash.
+/// - `out`: Destination of the digest, e.g. a field of a larger struct. A `&mut [u8]` of length 32
+///   converts with `try_into`.
+///
+/// # Examples
+///
+/// ```
+/// use shs_rs::sha256::{sha256, sha256_into};
+///
+/// let mut record = [0u8; 40];
+/// sha256_into(b"Hello, world!", (&mut record[8..]).try_into().unwrap());
+/// assert_eq!(record[8..], sha256(b"Hello, world!"));
+/// ```
+pub fn sha256_into(message: &[u8], out: &mut [u8; 32]) {
+    state_to_bytes_into(&hash_value_with_iv(IHV, message), out)
+}
 
-    #[test]
-    fn test_padding() {
-        // (input, expected_output)
-        let test_vectors = [
-            (vec![0x61], [vec![0x61, 0x80], vec![0; 61], vec![8]].concat()),
-            (vec![0x61, 0x62], [vec![0x61, 0x62, 0x80], vec![0; 60], vec![16]].concat()),
-            (
-                [vec![0x61, 0x62], vec![0; 64]].concat(),
-                [vec![0x61, 0x62], vec![0; 64], vec![128], vec![0; 59], vec![2, 16]].concat(),
-            ),
-        ];
+/// Compute SHA-256 digest of a secret message, then wipe the message.
+///
+/// The hasher state holding parts of `data` is wiped on drop as well.
+///
+/// # Parameters
+///
+/// - `data`: Input message to hash, overwritten with zeros before returning.
+///
+/// # Returns
+///
+/// 256-bit digest of the original contents of `data`.
+///
+/// # Examples
+///
+/// ```
+/// use shs_rs::sha256::{sha256, sha256_consume};
+///
+/// let mut password = *b"hunter2";
+/// let digest = sha256_consume(&mut password);
+/// assert_eq!(digest, sha256(b"hunter2"));
+/// assert_eq!(password, [0u8; 7]);
+/// ```
+#[cfg(feature = "zeroize")]
+#[must_use]
+pub fn sha256_consume(data: &mut [u8]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(data);
+    zeroize::Zeroize::zeroize(data);
+    hasher.finalize()
+}
 
-        for (input, expected) in test_vectors.into_iter() {
-            let input = input.clone();
-            let output = padding(&input);
-            assert_eq!(output.len() % 64, 0);
-            assert_eq!(output.len(), expected.len());
-            assert_eq!(output.to_vec(), expected);
-        }
+/// Compute SHA-256 digest of a byte repeated many times.
+///
+/// The message is never materialized: a stack buffer of the byte is fed to the compression
+/// function as often as needed.
+///
+/// # Parameters
+///
+/// - `byte`: Value of every message byte.
+/// - `count`: Length of the message.
+///
+/// # Returns
+///
+/// 256-bit digest of `count` copies of `byte`.
+///
+/// # Examples
+///
+/// ```
+/// use shs_rs::sha256::{sha256, sha256_repeated};
+///
+/// assert_eq!(sha256_repeated(b'a', 3), sha256(b"aaa"));
+/// ```
+#[must_use]
+pub fn sha256_repeated(byte: u8, count: usize) -> [u8; 32] {
+    // Whole blocks, so the hasher never has to buffer between updates
+    let chunk = [byte; 16 * BLOCK_SIZE];
+    let mut hasher = Sha256::new();
+    for _ in 0..count / chunk.len() {
+        hasher.update(&chunk);
     }
+    hasher.update(&chunk[..count % chunk.len()]);
+    hasher.finalize()
+}
 
-    #[test]
-    fn test_initial_hash_values() {
-        // Checks whether `IHV` vector contains correct values as per FIPS.
+/// Compute SHA-256 digest of anything that can be viewed as bytes.
+///
+/// A convenience wrapper around [`sha256`] accepting strings, vectors and arrays by value or by
+/// reference. Strings are hashed as their UTF-8 bytes as is, without Unicode normalization; see
+/// `sha256_str_nfc` with the `unicode` feature.
+///
+/// # Parameters
+///
+/// - `input`: Input message to hash.
+///
+/// # Returns
+///
+/// 256-bit digest of `input.as_ref()`.
+///
+/// # Examples
+///
+/// ```
+/// use shs_rs::sha256::{sha256, sha256_of};
+///
+/// let expected = sha256(b"abc");
+/// assert_eq!(sha256_of("abc"), expected);
+/// assert_eq!(sha256_of(String::from("abc")), expected);
+/// assert_eq!(sha256_of(vec![b'a', b'b', b'c']), expected);
+/// assert_eq!(sha256_of([0x61u8, 0x62, 0x63]), expected);
+/// assert_eq!(sha256_of(&b"abc"[..]), expected);
+/// ```
+#[must_use]
+pub fn sha256_of<T: AsRef<[u8]>>(input: T) -> [u8; 32] { sha256(input.as_ref()) }
 
-        // The first 8 prime numbers
-        let primes: [u32; 8] = [2, 3, 5, 7, 11, 13, 17, 19];
-        let generated_ihv: Vec<u32> = primes
-            .into_iter()
-            .map(|prime| {
-                // Calculate the square root and its fractional part
-                let sqrt_fractional = (prime as f64).sqrt() - (prime as f64).sqrt().floor();
-                // Convert the fractional part to a 32-bit word
-                (sqrt_fractional * (1_u64 << 32) as f64) as u32
-            })
-            .collect();
-        let generated_ihv: [u32; 8] = generated_ihv.try_into().unwrap();
+/// Compute SHA-256 digest of a string after Unicode NFC normalization.
+///
+/// Canonically equivalent strings, e.g. "é" as the single code point U+00E9 or as "e" followed by
+/// the combining acute accent U+0301, look the same but have different UTF-8 bytes, so
+/// `sha256(s.as_bytes())` gives them different digests. This function hashes the UTF-8 bytes of
+/// the NFC form instead, which is identical for canonically equivalent strings. The normalized
+/// string is streamed into the hasher without being allocated.
+///
+/// # Parameters
+///
+/// - `s`: String to normalize and hash.
+///
+/// # Returns
+///
+/// 256-bit digest of the UTF-8 encoding of the NFC form of `s`.
+///
+/// # Examples
+///
+/// ```
+/// use shs_rs::sha256::{sha256, sha256_str_nfc};
+///
+/// assert_eq!(sha256_str_nfc("cafe\u{301}"), sha256_str_nfc("caf\u{e9}"));
+/// assert_eq!(sha256_str_nfc("cafe\u{301}"), sha256("caf\u{e9}".as_bytes()));
+/// ```
+#[cfg(feature = "unicode")]
+#[must_use]
+pub fn sha256_str_nfc(s: &str) -> [u8; 32] {
+    use unicode_normalization::UnicodeNormalization;
 
-        assert_eq!(IHV, generated_ihv);
+    let mut hasher = Sha256::new();
+    let mut utf8 = [0u8; 4];
+    for c in s.nfc() {
+        hasher.update(c.encode_utf8(&mut utf8).as_bytes());
     }
+    hasher.finalize()
+}
 
-    #[test]
-    fn test_words_k() {
-        // Checks whether `WORDS_K` vector contains correct values as per FIPS.
-
-        // The first 64 prime numbers
-        let primes: [u32; 64] = [
-            2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61, 67, 71, 73, 79, 83,
-            89, 97, 101, 103, 107, 109, 113, 127, 131, 137, 139, 149, 151, 157, 163, 167, 173, 179,
-            181, 191, 193, 197, 199, 211, 223, 227, 229, 233, 239, 241, 251, 257, 263, 269, 271,
-            277, 281, 283, 293, 307, 311,
+/// Compute SHA-256 digest of a 32-byte message, e.g. a digest being re-hashed.
+///
+/// Padding of a 32-byte message always fits in the same block, so it is built directly and the
+/// digest takes exactly one compression.
+///
+/// # Parameters
+///
+/// - `message`: Input message to hash.
+///
+/// # Returns
+///
+/// 256-bit digest of the `message`, same as [`sha256`].
+///
+/// # Examples
+///
+/// ```
+/// use shs_rs::sha256::{sha256, sha256_block32};
+///
+/// let leaf = sha256(b"leaf");
+/// assert_eq!(sha256_block32(&leaf), sha256(&leaf));
+/// ```
+#[must_use]
+pub fn sha256_block32(message: &[u8; 32]) -> [u8; 32] {
+    let mut block = [0u8; BLOCK_SIZE];
+    block[..32].copy_from_slice(message);
+    block[32] = 0x80;
+    block[BLOCK_SIZE - 8..].copy_from_slice(&(32u64 * 8).to_be_bytes());
+
+    let mut hash_value = IHV;
+    compress_blocks(&mut hash_value, &block);
+    state_to_bytes(&hash_value)
+}
+
+/// Padding block following a 64-byte message.
+///
+/// See: FIPS 180-4, 5.1.1
+const PADDING_BLOCK64: [u8; BLOCK_SIZE] = {
+    let mut block = [0u8; BLOCK_SIZE];
+    block[0] = 0x80;
+    // Length of 512 bits
+    block[BLOCK_SIZE - 2] = 0x02;
+    block
+};
+
+/// Compute SHA-256 digest of a 64-byte message, e.g. two concatenated Merkle tree nodes.
+///
+/// The message is exactly one block and its padding a constant second block, so the digest takes
+/// exactly two compressions without copying the message.
+///
+/// # Parameters
+///
+/// - `message`: Input message to hash.
+///
+/// # Returns
+///
+/// 256-bit digest of the `message`, same as [`sha256`].
+///
+/// # Examples
+///
+/// ```
+/// use shs_rs::sha256::{sha256, sha256_block64};
+///
+/// assert_eq!(sha256_block64(&[7u8; 64]), sha256(&[7u8; 64]));
+/// ```
+#[must_use]
+pub fn sha256_block64(message: &[u8; 64]) -> [u8; 32] {
+    let mut hash_value = IHV;
+    compress_blocks(&mut hash_value, message);
+    compress_blocks(&mut hash_value, &PADDING_BLOCK64);
+    state_to_bytes(&hash_value)
+}
+
+/// Compute SHA-256 digest of a message, starting from a custom initial hash value.
+///
+/// The message is padded as usual, with the length field counting only `message` itself, not
+/// whatever data produced `iv`.
+///
+/// # Security
+///
+/// The output is only a SHA-256 digest when `iv` is [`IHV`]. With any other value the standard
+/// collision and preimage guarantees no longer apply: an attacker who can choose the IV controls
+/// the chaining value directly. Use this for custom chaining constructions and for studying
+/// length extension, not as a general-purpose hash.
+///
+/// # Parameters
+///
+/// - `iv`: Initial hash value to start from.
+/// - `message`: Input message to hash.
+///
+/// # Returns
+///
+/// 256-bit digest of the `message` chained from `iv`.
+///
+/// # Examples
+///
+/// ```
+/// use shs_rs::sha256::{sha256, sha256_with_iv, IHV};
+///
+/// assert_eq!(sha256_with_iv(IHV, b"abc"), sha256(b"abc"));
+/// ```
+#[must_use]
+pub fn sha256_with_iv(iv: [u32; 8], message: &[u8]) -> [u8; 32] {
+    state_to_bytes(&hash_value_with_iv(iv, message))
+}
+
+/// Hash a padded message from `iv` to its final hash value.
+fn hash_value_with_iv(iv: [u32; 8], message: &[u8]) -> [u32; 8] {
+    let mut hash_value = iv;
+
+    // Divide the message into 512-bit blocks: FIPS 180-4, 5.2.1
+    compress_blocks(&mut hash_value, message);
+
+    // Only the final partial block needs padding
+    let mut tail = [0u8; 128];
+    let tail_blocks = padding_into(message, &mut tail);
+    compress_blocks(&mut hash_value, &tail[..tail_blocks * BLOCK_SIZE]);
+
+    hash_value
+}
+
+/// Compute SHA-256 digest of a message on a path that cannot panic.
+///
+/// Meant for firmware that must not panic. The message is hashed with the portable compression
+/// function only, from fixed-size stack buffers, and every block is loaded through an array
+/// reference, so there are no bounds checks left that could fail.
+///
+/// With the `no-panic` feature, this is checked by the [`no-panic`](https://docs.rs/no-panic)
+/// crate: a release build fails to link if the optimizer cannot prove that the function never
+/// panics. The check has limits:
+///
+/// - It only runs with optimizations, so debug builds are not checked.
+/// - It proves the absence of panics, not correctness or constant-time execution.
+/// - It covers this function and everything inlined into it, not the hardware backends used by
+///   [`sha256`], which may be faster.
+///
+/// # Parameters
+///
+/// - `message`: Input message to hash.
+///
+/// # Returns
+///
+/// 256-bit digest of the `message`, equal to [`sha256`].
+///
+/// # Examples
+///
+/// ```
+/// use shs_rs::sha256::{sha256, sha256_nostd};
+/// assert_eq!(sha256_nostd(b"abc"), sha256(b"abc"));
+/// ```
+#[cfg_attr(all(feature = "no-panic", not(debug_assertions)), no_panic::no_panic)]
+#[must_use]
+pub fn sha256_nostd(message: &[u8]) -> [u8; 32] {
+    let mut hash_value = IHV;
+    for block in blocks(message) {
+        compress_block(&mut hash_value, block);
+    }
+
+    // Pad the remainder in one or two blocks, see FIPS 180-4, 5.1.1
+    let remainder = message.chunks_exact(BLOCK_SIZE).remainder();
+    let mut tail = [[0u8; BLOCK_SIZE]; 2];
+    for (byte, &message_byte) in tail[0].iter_mut().zip(remainder) {
+        *byte = message_byte;
+    }
+    if let Some(byte) = tail[0].get_mut(remainder.len()) {
+        *byte = 0x80;
+    }
+    let two_blocks = remainder.len() >= BLOCK_SIZE - 8;
+    let length_block = if two_blocks { &mut tail[1] } else { &mut tail[0] };
+    let bit_length = (message.len() as u64).wrapping_mul(8);
+    for (byte, length_byte) in
+        length_block[BLOCK_SIZE - 8..].iter_mut().zip(bit_length.to_be_bytes())
+    {
+        *byte = length_byte;
+    }
+
+    compress_block(&mut hash_value, &tail[0]);
+    if two_blocks {
+        compress_block(&mut hash_value, &tail[1]);
+    }
+    state_to_bytes(&hash_value)
+}
+
+/// Compute the SHA-256 digest of a message as a [`Digest`].
+///
+/// # Parameters
+///
+/// - `message`: Input message to hash.
+///
+/// # Returns
+///
+/// 256-bit digest of the `message`, formattable as hex and compared in constant time.
+#[must_use]
+pub fn sha256_digest(message: &[u8]) -> Digest { Digest(sha256(message)) }
+
+/// Compute the SHA-256 digest of a message as lowercase hex.
+///
+/// # Parameters
+///
+/// - `message`: Input message to hash.
+///
+/// # Returns
+///
+/// The 64-character hex encoding of the 256-bit digest of `message`.
+///
+/// # Examples
+///
+/// ```
+/// use shs_rs::sha256::sha256_hex;
+/// assert_eq!(
+///     sha256_hex(b"abc"),
+///     "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+/// );
+/// ```
+#[cfg(feature = "alloc")]
+#[must_use]
+pub fn sha256_hex(message: &[u8]) -> String { digest_to_hex(&sha256(message), false) }
+
+/// Compute the SHA-256 digest of a message as uppercase hex.
+///
+/// # Parameters
+///
+/// - `message`: Input message to hash.
+///
+/// # Returns
+///
+/// The 64-character hex encoding of the 256-bit digest of `message`.
+#[cfg(feature = "alloc")]
+#[must_use]
+pub fn sha256_hex_upper(message: &[u8]) -> String { digest_to_hex(&sha256(message), true) }
+
+/// Hex encode a digest.
+#[cfg(feature = "alloc")]
+fn digest_to_hex(digest: &[u8; 32], upper: bool) -> String {
+    let mut out = [0u8; 64];
+    encode_hex(digest, &mut out, upper);
+    out.iter().map(|&digit| digit as char).collect()
+}
+
+/// Compute the SHA-256 digest of a message as standard, padded base64.
+///
+/// This is the encoding used by Subresource Integrity, e.g. `integrity="sha256-<base64>"`.
+///
+/// # Parameters
+///
+/// - `message`: Input message to hash.
+///
+/// # Returns
+///
+/// The 44-character base64 encoding of the 256-bit digest of `message`.
+///
+/// # Examples
+///
+/// ```
+/// use shs_rs::sha256::sha256_base64;
+/// assert_eq!(sha256_base64(b"abc"), "ungWv48Bz+pBQUDeXa4iI7ADYaOWF3qctBD/YfIAFa0=");
+/// ```
+#[cfg(feature = "base64")]
+#[must_use]
+pub fn sha256_base64(message: &[u8]) -> String {
+    use base64::Engine;
+    base64::engine::general_purpose::STANDARD.encode(sha256(message))
+}
+
+/// Compute SHA-256d, the double SHA-256 digest `sha256(sha256(message))` of a message.
+///
+/// The intermediate digest always pads into a single block, which is compressed directly.
+///
+/// # Parameters
+///
+/// - `message`: Input message to hash.
+///
+/// # Returns
+///
+/// 256-bit digest of the SHA-256 digest of `message`.
+///
+/// # Examples
+///
+/// ```
+/// use shs_rs::sha256::{sha256, sha256d};
+/// assert_eq!(sha256d(b"abc"), sha256(&sha256(b"abc")));
+/// ```
+#[must_use]
+pub fn sha256d(message: &[u8]) -> [u8; 32] {
+    let mut block = [0u8; BLOCK_SIZE];
+    block[..32].copy_from_slice(&sha256(message));
+
+    // Append "1" bit, then the 256-bit length as 64-bit big-endian integer
+    block[32] = 0x80;
+    block[BLOCK_SIZE - 8..].copy_from_slice(&256u64.to_be_bytes());
+
+    let mut hash_value = IHV;
+    compress_blocks(&mut hash_value, &block);
+    state_to_bytes(&hash_value)
+}
+
+/// Compute the SHA-256 digest of a message given as scattered pieces.
+///
+/// The pieces are absorbed in order as if they were concatenated, without copying them into one
+/// buffer first. Block boundaries may fall anywhere, including inside a piece.
+///
+/// # Parameters
+///
+/// - `slices`: Pieces of the message, e.g. the contents of a list of `std::io::IoSlice`s. Empty
+///   pieces contribute nothing.
+///
+/// # Returns
+///
+/// 256-bit digest of the concatenation of `slices`.
+///
+/// # Examples
+///
+/// ```
+/// use shs_rs::sha256::{sha256, sha256_vectored};
+/// assert_eq!(sha256_vectored(&[b"Hello, ", b"world!"]), sha256(b"Hello, world!"));
+/// ```
+#[must_use]
+pub fn sha256_vectored(slices: &[&[u8]]) -> [u8; 32] { sha256_chunks(slices.iter().copied()) }
+
+/// Compute the SHA-256 digest of a message produced lazily in pieces.
+///
+/// Like [`sha256_vectored`], but the pieces come from an iterator, e.g. lines yielded by a parser,
+/// and are hashed as they are produced without collecting them first.
+///
+/// # Parameters
+///
+/// - `chunks`: Pieces of the message in order. Empty pieces contribute nothing.
+///
+/// # Returns
+///
+/// 256-bit digest of the concatenation of `chunks`.
+///
+/// # Examples
+///
+/// ```
+/// use shs_rs::sha256::{sha256, sha256_chunks};
+///
+/// let text = "first line\nsecond line";
+/// let digest = sha256_chunks(text.split_inclusive('\n').map(str::as_bytes));
+/// assert_eq!(digest, sha256(text.as_bytes()));
+/// ```
+#[must_use]
+pub fn sha256_chunks<'a, I: IntoIterator<Item = &'a [u8]>>(chunks: I) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    for chunk in chunks {
+        hasher.update(chunk);
+    }
+    hasher.finalize()
+}
+
+/// Compute the SHA-256 digest of a message with a region of it treated as zeros.
+///
+/// For formats that hash a message with a placeholder zeroed, e.g. a signature field inside the
+/// signed data, without allocating a zeroed copy. Each block is copied into a stack buffer, and
+/// every byte is masked arithmetically with a constant-time comparison of its index against
+/// `mask_range`, so there is no branch on whether a byte is masked. The range bounds themselves
+/// are treated as public.
+///
+/// # Parameters
+///
+/// - `message`: Input message to hash.
+/// - `mask_range`: Byte range of `message` to replace with zeros, may be empty.
+///
+/// # Returns
+///
+/// 256-bit digest of `message` with the bytes in `mask_range` zeroed.
+///
+/// # Panics
+///
+/// If `mask_range` is decreasing or extends past the end of `message`.
+///
+/// # Examples
+///
+/// ```
+/// use shs_rs::sha256::{sha256, sha256_with_mask};
+///
+/// let signed = b"header|SIGNATURE|body";
+/// assert_eq!(sha256_with_mask(signed, 7..16), sha256(b"header|\0\0\0\0\0\0\0\0\0|body"));
+/// ```
+#[must_use]
+pub fn sha256_with_mask(message: &[u8], mask_range: Range<usize>) -> [u8; 32] {
+    assert!(
+        mask_range.start <= mask_range.end && mask_range.end <= message.len(),
+        "mask range {:?} out of bounds for message of length {}",
+        mask_range,
+        message.len()
+    );
+    let (start, end) = (mask_range.start as u64, mask_range.end as u64);
+
+    let mut hasher = Sha256::new();
+    let mut block = [0u8; BLOCK_SIZE];
+    for (offset, chunk) in (0..).step_by(BLOCK_SIZE).zip(message.chunks(BLOCK_SIZE)) {
+        for (i, (masked, &byte)) in block.iter_mut().zip(chunk).enumerate() {
+            let index = (offset + i) as u64;
+            let in_range = !index.ct_lt(&start) & index.ct_lt(&end);
+            *masked = u8::conditional_select(&byte, &0, in_range);
+        }
+        hasher.update(&block[..chunk.len()]);
+    }
+    hasher.finalize()
+}
+
+/// Compute the SHA-256 digest of a message truncated to its first `N` bytes.
+///
+/// # Parameters
+///
+/// - `message`: Input message to hash.
+///
+/// # Returns
+///
+/// The leftmost `N` bytes of the 256-bit digest of `message`. Choosing `N > 32` fails to compile.
+///
+/// # Examples
+///
+/// ```
+/// use shs_rs::sha256::{sha256, sha256_truncated};
+/// assert_eq!(sha256_truncated::<16>(b"abc"), sha256(b"abc")[..16]);
+/// ```
+///
+/// ```compile_fail
+/// let digest = shs_rs::sha256::sha256_truncated::<33>(b"abc");
+/// ```
+#[must_use]
+pub fn sha256_truncated<const N: usize>(message: &[u8]) -> [u8; N] {
+    const { assert!(N <= 32, "truncated digest can't be longer than 32 bytes") };
+
+    let mut result = [0u8; N];
+    result.copy_from_slice(&sha256(message)[..N]);
+    result
+}
+
+/// Compute SHA-256 digests of many independent messages.
+///
+/// With the `rayon` feature the messages are spread across threads, otherwise they are hashed one
+/// after another.
+///
+/// # Parameters
+///
+/// - `messages`: Input messages to hash.
+///
+/// # Returns
+///
+/// 256-bit digest of each message, in the same order as `messages`.
+///
+/// # Examples
+///
+/// ```
+/// use shs_rs::sha256::{sha256, sha256_batch};
+/// let digests = sha256_batch(&[b"leaf 0", b"leaf 1"]);
+/// assert_eq!(digests[1], sha256(b"leaf 1"));
+/// ```
+#[cfg(feature = "alloc")]
+#[must_use]
+pub fn sha256_batch(messages: &[&[u8]]) -> Vec<[u8; 32]> {
+    #[cfg(feature = "rayon")]
+    {
+        use rayon::prelude::*;
+        messages.par_iter().map(|message| sha256(message)).collect()
+    }
+    #[cfg(not(feature = "rayon"))]
+    {
+        messages.iter().map(|message| sha256(message)).collect()
+    }
+}
+
+/// Compare two digests in constant time.
+///
+/// All 32 bytes are always compared, so the time taken does not reveal the position of the first
+/// mismatch. Use this instead of `==` when checking MACs or commitments.
+///
+/// # Parameters
+///
+/// - `expected`: Digest known to be correct, e.g. a stored checksum.
+/// - `actual`: Freshly computed digest.
+///
+/// # Returns
+///
+/// [`Choice`] holding `1` if the digests are equal and `0` otherwise.
+///
+/// # Examples
+///
+/// ```
+/// use shs_rs::sha256::{sha256, verify};
+///
+/// let stored = sha256(b"Hello, world!");
+/// assert!(bool::from(verify(&stored, &sha256(b"Hello, world!"))));
+/// ```
+#[must_use]
+pub fn verify(expected: &[u8; 32], actual: &[u8; 32]) -> Choice { expected.ct_eq(actual) }
+
+/// Compute the SHA-256 digest of one of two messages without branching on which.
+///
+/// Both messages are always hashed and the result is picked byte by byte in constant time. The
+/// time taken depends on the lengths though, so `choice` only stays hidden if `a` and `b` are
+/// equally long.
+///
+/// # Parameters
+///
+/// - `choice`: `0` to hash `a`, `1` to hash `b`, following [`ConditionallySelectable`].
+/// - `a`, `b`: Candidate messages, of the same length.
+///
+/// # Returns
+///
+/// 256-bit digest of the selected message.
+///
+/// # Examples
+///
+/// ```
+/// use shs_rs::sha256::{sha256, sha256_select};
+/// use subtle::Choice;
+///
+/// assert_eq!(sha256_select(Choice::from(1), b"left", b"rght"), sha256(b"rght"));
+/// ```
+#[must_use]
+pub fn sha256_select(choice: Choice, a: &[u8], b: &[u8]) -> [u8; 32] {
+    let (digest_a, digest_b) = (sha256(a), sha256(b));
+    let mut selected = [0u8; 32];
+    for ((out, x), y) in selected.iter_mut().zip(digest_a).zip(digest_b) {
+        *out = u8::conditional_select(&x, &y, choice);
+    }
+    selected
+}
+
+/// Size of a SHA-256 message block in bytes.
+pub const BLOCK_SIZE: usize = 64;
+
+/// Longest message that can be hashed, in bytes.
+///
+/// The padding encodes the message length in bits as a 64-bit integer, so messages are limited to
+/// `2^64 - 1` bits.
+///
+/// See: FIPS 180-4, 1
+pub const MAX_INPUT_LEN: u64 = u64::MAX / 8;
+
+/// Incremental SHA-256 hasher.
+///
+/// Accepts the message in arbitrarily sized pieces and produces the same digest as [`sha256`]
+/// over their concatenation. Only a single partial block is buffered at any time.
+///
+/// Cloning forks the computation, so a common prefix is absorbed only once. The hasher is not
+/// `Copy`: it may hold secret material, which with the `zeroize` feature is wiped on drop, so
+/// duplicating it has to be explicit.
+///
+/// # Examples
+///
+/// ```
+/// use shs_rs::sha256::{sha256, Sha256};
+///
+/// let mut hasher = Sha256::new();
+/// hasher.update(b"Hello, ");
+/// hasher.update(b"world!");
+/// assert_eq!(hasher.finalize(), sha256(b"Hello, world!"));
+/// ```
+#[derive(Clone)]
+pub struct Sha256 {
+    state:      [u32; 8],
+    buffer:     [u8; BLOCK_SIZE],
+    buffer_len: usize,
+    total_len:  u64,
+}
+
+impl OutputSize for Sha256 {
+    const OUTPUT_SIZE: usize = 32;
+}
+
+impl HashFunction for Sha256 {
+    type Output = [u8; 32];
+
+    fn digest(input: &[u8]) -> [u8; 32] { sha256(input) }
+}
+
+impl Sha256 {
+    /// Create a hasher starting from the initial hash value [`IHV`].
+    pub fn new() -> Self {
+        Self { state: IHV, buffer: [0; BLOCK_SIZE], buffer_len: 0, total_len: 0 }
+    }
+
+    /// Absorb more of the message.
+    ///
+    /// # Parameters
+    ///
+    /// - `data`: Next piece of the message.
+    ///
+    /// # Panics
+    ///
+    /// If the message would grow beyond [`MAX_INPUT_LEN`] bytes, see [`Sha256::try_update`].
+    pub fn update(&mut self, data: &[u8]) {
+        if let Err(err) = self.try_update(data) {
+            panic!("{}", err);
+        }
+    }
+
+    /// Absorb more of the message, builder style.
+    ///
+    /// # Parameters
+    ///
+    /// - `data`: Next piece of the message.
+    ///
+    /// # Returns
+    ///
+    /// The hasher, for further chaining or [`Sha256::finalize`].
+    ///
+    /// # Panics
+    ///
+    /// If the message would grow beyond [`MAX_INPUT_LEN`] bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use shs_rs::sha256::{sha256, Sha256};
+    ///
+    /// let digest = Sha256::new().chain(b"Hello, ").chain(b"world!").finalize();
+    /// assert_eq!(digest, sha256(b"Hello, world!"));
+    /// ```
+    #[must_use]
+    pub fn chain(mut self, data: &[u8]) -> Self {
+        self.update(data);
+        self
+    }
+
+    /// Absorb a 32-bit integer as 4 big-endian bytes.
+    ///
+    /// # Parameters
+    ///
+    /// - `value`: Integer to absorb.
+    pub fn update_u32_be(&mut self, value: u32) { self.update(&value.to_be_bytes()); }
+
+    /// Absorb a 64-bit integer as 8 big-endian bytes.
+    ///
+    /// # Parameters
+    ///
+    /// - `value`: Integer to absorb.
+    pub fn update_u64_be(&mut self, value: u64) { self.update(&value.to_be_bytes()); }
+
+    /// Absorb a variable-length field as its length, 4 big-endian bytes, followed by its bytes.
+    ///
+    /// With every field prefixed, a sequence of fields has a single encoding: `("ab", "c")` and
+    /// `("a", "bc")` hash differently, while plain [`Sha256::update`] calls would absorb `"abc"`
+    /// for both.
+    ///
+    /// # Parameters
+    ///
+    /// - `data`: Field to absorb.
+    ///
+    /// # Panics
+    ///
+    /// If `data` is longer than `u32::MAX` bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use shs_rs::sha256::{sha256, Sha256};
+    ///
+    /// let mut hasher = Sha256::new();
+    /// hasher.update_length_prefixed(b"alice");
+    /// hasher.update_u64_be(42);
+    /// let mut expected = vec![0, 0, 0, 5];
+    /// expected.extend_from_slice(b"alice");
+    /// expected.extend_from_slice(&42u64.to_be_bytes());
+    /// assert_eq!(hasher.finalize(), sha256(&expected));
+    /// ```
+    pub fn update_length_prefixed(&mut self, data: &[u8]) {
+        let len = u32::try_from(data.len()).expect("length-prefixed field exceeds u32::MAX bytes");
+        self.update_u32_be(len);
+        self.update(data);
+    }
+
+    /// Absorb bytes produced one at a time, e.g. by a decoder.
+    ///
+    /// Equivalent to collecting `iter` and passing it to [`Sha256::update`], but the bytes are
+    /// gathered a block at a time on the stack instead.
+    ///
+    /// # Parameters
+    ///
+    /// - `iter`: Next piece of the message.
+    ///
+    /// # Panics
+    ///
+    /// If the message would grow beyond [`MAX_INPUT_LEN`] bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use shs_rs::sha256::{sha256, Sha256};
+    ///
+    /// let mut hasher = Sha256::new();
+    /// hasher.update_iter(b"Hello, world!".iter().map(|b| b.to_ascii_uppercase()));
+    /// assert_eq!(hasher.finalize(), sha256(b"HELLO, WORLD!"));
+    /// ```
+    pub fn update_iter<I: IntoIterator<Item = u8>>(&mut self, iter: I) {
+        let mut chunk = [0u8; BLOCK_SIZE];
+        let mut len = 0;
+        for byte in iter {
+            chunk[len] = byte;
+            len += 1;
+            if len == BLOCK_SIZE {
+                self.update(&chunk);
+                len = 0;
+            }
+        }
+        self.update(&chunk[..len]);
+    }
+
+    /// Absorb more of the message, unless it would become too long to hash.
+    ///
+    /// See: FIPS 180-4, 5.1.1
+    ///
+    /// # Parameters
+    ///
+    /// - `data`: Next piece of the message.
+    ///
+    /// # Returns
+    ///
+    /// [`ShaError::InputTooLong`], without absorbing any of `data`, if the message would exceed
+    /// [`MAX_INPUT_LEN`] bytes.
+    pub fn try_update(&mut self, mut data: &[u8]) -> Result<(), ShaError> {
+        self.total_len = match self.total_len.checked_add(data.len() as u64) {
+            Some(total_len) if total_len <= MAX_INPUT_LEN => total_len,
+            _ =>
+                return Err(ShaError::InputTooLong(
+                    self.total_len.saturating_add(data.len() as u64),
+                )),
+        };
+        trace_progress("Sha256::update", self.total_len - data.len() as u64, data.len() as u64);
+
+        // Top up a previously buffered partial block
+        if self.buffer_len > 0 {
+            let take = (BLOCK_SIZE - self.buffer_len).min(data.len());
+            self.buffer[self.buffer_len..self.buffer_len + take].copy_from_slice(&data[..take]);
+            self.buffer_len += take;
+            data = &data[take..];
+            if self.buffer_len < BLOCK_SIZE {
+                return Ok(());
+            }
+            compress_blocks(&mut self.state, &self.buffer);
+            self.buffer_len = 0;
+        }
+
+        // Process complete blocks straight from the input
+        let (blocks, remainder) = data.split_at(data.len() - data.len() % BLOCK_SIZE);
+        compress_blocks(&mut self.state, blocks);
+
+        // Keep the remainder for later
+        self.buffer[..remainder.len()].copy_from_slice(remainder);
+        self.buffer_len = remainder.len();
+        Ok(())
+    }
+
+    /// Absorb whole blocks, e.g. frames of a transport that already splits data at 64 bytes.
+    ///
+    /// The blocks are compressed in place, without being copied through the internal buffer.
+    ///
+    /// # Parameters
+    ///
+    /// - `blocks`: Next 512-bit blocks of the message.
+    ///
+    /// # Panics
+    ///
+    /// If a partial block is buffered, i.e. the bytes absorbed so far are not a multiple of 64,
+    /// or if the message would grow beyond [`MAX_INPUT_LEN`] bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use shs_rs::sha256::{sha256, Sha256};
+    ///
+    /// let frames = [[0x11u8; 64], [0x22; 64]];
+    /// let mut hasher = Sha256::new();
+    /// hasher.update_blocks(&[&frames[0], &frames[1]]);
+    /// hasher.update(b"tail");
+    /// assert_eq!(hasher.finalize(), sha256(&[&frames.concat()[..], b"tail"].concat()));
+    /// ```
+    pub fn update_blocks(&mut self, blocks: &[&[u8; BLOCK_SIZE]]) {
+        assert_eq!(self.buffer_len, 0, "update_blocks called with a partial block buffered");
+        let len = (blocks.len() as u64).saturating_mul(BLOCK_SIZE as u64);
+        self.total_len = match self.total_len.checked_add(len) {
+            Some(total_len) if total_len <= MAX_INPUT_LEN => total_len,
+            _ => panic!("{}", ShaError::InputTooLong(self.total_len.saturating_add(len))),
+        };
+        trace_progress("Sha256::update_blocks", self.total_len - len, len);
+
+        for block in blocks {
+            compress_blocks(&mut self.state, &block[..]);
+        }
+    }
+
+    /// Number of message bytes absorbed so far.
+    ///
+    /// Counts everything passed to [`Sha256::update`] and its variants, including bytes still
+    /// buffered in a partial block.
+    pub fn bytes_processed(&self) -> u64 { self.total_len }
+
+    /// Digest of the message absorbed so far, leaving the hasher able to keep absorbing.
+    ///
+    /// Finalizes a copy of the state, so it costs one or two compressions but no rehashing. Useful
+    /// to record the running hash at each flush of a resumable upload.
+    ///
+    /// # Returns
+    ///
+    /// The digest [`Sha256::finalize`] would return now.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use shs_rs::sha256::{sha256, Sha256};
+    ///
+    /// let mut hasher = Sha256::new();
+    /// hasher.update(b"part 1,");
+    /// let checkpoint = hasher.checkpoint();
+    /// hasher.update(b" part 2");
+    /// assert_eq!(checkpoint, sha256(b"part 1,"));
+    /// assert_eq!(hasher.finalize(), sha256(b"part 1, part 2"));
+    /// ```
+    #[must_use]
+    pub fn checkpoint(&self) -> [u8; 32] { self.clone().finalize() }
+
+    /// Pad the absorbed message and compute its digest.
+    ///
+    /// See: FIPS 180-4, 5.1.1
+    ///
+    /// # Returns
+    ///
+    /// 256-bit digest of all data passed to [`Sha256::update`].
+    #[must_use]
+    pub fn finalize(self) -> [u8; 32] { state_to_bytes(&self.finalize_words()) }
+
+    /// Pad the absorbed message and write its digest into an existing buffer.
+    ///
+    /// # Parameters
+    ///
+    /// - `out`: Destination of the 256-bit digest of all data passed to [`Sha256::update`].
+    pub fn finalize_into(self, out: &mut [u8; 32]) {
+        state_to_bytes_into(&self.finalize_words(), out)
+    }
+
+    /// Pad the absorbed message and compute the final hash value as words.
+    ///
+    /// See: FIPS 180-4, 6.2.2
+    ///
+    /// # Returns
+    ///
+    /// The eight words `H_0..H_7` as native integers. The byte digest returned by
+    /// [`Sha256::finalize`] is each of them serialized big-endian, in order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use shs_rs::sha256::{sha256, Sha256};
+    ///
+    /// let mut hasher = Sha256::new();
+    /// hasher.update(b"abc");
+    /// let words = hasher.finalize_words();
+    /// assert_eq!(words[0], 0xba7816bf);
+    /// assert_eq!(words[0].to_be_bytes(), sha256(b"abc")[..4]);
+    /// ```
+    #[must_use]
+    pub fn finalize_words(mut self) -> [u32; 8] {
+        // At most `MAX_INPUT_LEN` bytes, so the bit length fits in 64 bits
+        let l_bits = self.total_len.wrapping_mul(8);
+
+        // Append "1" bit to the end of message
+        self.buffer[self.buffer_len] = 0x80;
+        self.buffer[self.buffer_len + 1..].fill(0);
+
+        // Not enough room for the length, spill into another block
+        if self.buffer_len + 1 > BLOCK_SIZE - 8 {
+            compress_blocks(&mut self.state, &self.buffer);
+            self.buffer.fill(0);
+        }
+
+        // Append length as 64-bit big-endian integer
+        self.buffer[BLOCK_SIZE - 8..].copy_from_slice(&l_bits.to_be_bytes());
+        compress_blocks(&mut self.state, &self.buffer);
+
+        self.state
+    }
+
+    /// Compute the digest and reset the hasher for the next message.
+    ///
+    /// Equivalent to `finalize` followed by starting over with [`Sha256::new`], but keeps using
+    /// the same instance. The old state is dropped, and wiped with the `zeroize` feature.
+    ///
+    /// # Returns
+    ///
+    /// 256-bit digest of all data passed to [`Sha256::update`] since the last reset.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use shs_rs::sha256::{sha256, Sha256};
+    ///
+    /// let mut hasher = Sha256::new();
+    /// for message in [&b"first"[..], b"second"] {
+    ///     hasher.update(message);
+    ///     assert_eq!(hasher.finalize_reset(), sha256(message));
+    /// }
+    /// ```
+    #[must_use]
+    pub fn finalize_reset(&mut self) -> [u8; 32] { core::mem::take(self).finalize() }
+
+    /// Discard all absorbed data, going back to the initial hash value [`IHV`].
+    pub fn reset(&mut self) { *self = Self::new(); }
+
+    /// Compute the digest and compare it to an expected one in constant time.
+    ///
+    /// # Parameters
+    ///
+    /// - `expected`: Digest the absorbed message should have.
+    ///
+    /// # Returns
+    ///
+    /// [`Choice`] holding `1` if the digest of all data passed to [`Sha256::update`] equals
+    /// `expected` and `0` otherwise, see [`verify`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use shs_rs::sha256::{sha256, Sha256};
+    ///
+    /// let mut hasher = Sha256::new();
+    /// hasher.update(b"Hello, ");
+    /// hasher.update(b"world!");
+    /// assert!(bool::from(hasher.finalize_verify(&sha256(b"Hello, world!"))));
+    /// ```
+    #[must_use]
+    pub fn finalize_verify(self, expected: &[u8; 32]) -> Choice {
+        verify(expected, &self.finalize())
+    }
+}
+
+impl Default for Sha256 {
+    fn default() -> Self { Self::new() }
+}
+
+/// Hasher that has already absorbed the slice, e.g. a prefix to [`Sha256::chain`] onto.
+impl From<&[u8]> for Sha256 {
+    fn from(data: &[u8]) -> Self { Self::new().chain(data) }
+}
+
+/// Feeds written bytes into [`Sha256::update`]; writes never fail or fall short.
+#[cfg(feature = "std")]
+impl std::io::Write for Sha256 {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.update(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> { Ok(()) }
+}
+
+/// [`Hasher`](core::hash::Hasher) backed by SHA-256, for keying hash maps by SHA-256 of their keys.
+///
+/// [`finish`](core::hash::Hasher::finish) is the first 8 bytes of the digest as a big-endian
+/// `u64`. That is enough to spread keys over buckets, but far too short for integrity checks; use
+/// [`Sha256`] for those.
+///
+/// # Examples
+///
+/// ```
+/// use std::{collections::HashMap, hash::BuildHasherDefault};
+///
+/// use shs_rs::sha256::Sha256Hasher;
+///
+/// let mut map: HashMap<&str, u32, BuildHasherDefault<Sha256Hasher>> = HashMap::default();
+/// map.insert("answer", 42);
+/// assert_eq!(map.get("answer"), Some(&42));
+/// ```
+#[derive(Clone, Default)]
+pub struct Sha256Hasher(Sha256);
+
+impl core::hash::Hasher for Sha256Hasher {
+    fn write(&mut self, bytes: &[u8]) { self.0.update(bytes); }
+
+    fn finish(&self) -> u64 {
+        // Finalize a copy, more bytes may still be written
+        let digest = self.0.clone().finalize();
+        u64::from_be_bytes([
+            digest[0], digest[1], digest[2], digest[3], digest[4], digest[5], digest[6], digest[7],
+        ])
+    }
+}
+
+/// Wipe the hash value, buffered message bytes, and length counter, which may hold key-derived
+/// material when hashing secrets.
+#[cfg(feature = "zeroize")]
+impl Drop for Sha256 {
+    fn drop(&mut self) {
+        use zeroize::Zeroize;
+
+        self.state.zeroize();
+        self.buffer.zeroize();
+        self.buffer_len.zeroize();
+        self.total_len.zeroize();
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl zeroize::ZeroizeOnDrop for Sha256 {}
+
+/// Object-safe interface of the RustCrypto `digest` crate, for choosing a hash at runtime behind a
+/// `Box<dyn DynDigest>`.
+#[cfg(feature = "digest")]
+impl digest::DynDigest for Sha256 {
+    fn update(&mut self, data: &[u8]) { Sha256::update(self, data); }
+
+    fn finalize_into(self, buf: &mut [u8]) -> Result<(), digest::InvalidBufferSize> {
+        let out: &mut [u8; 32] = buf.try_into().map_err(|_| digest::InvalidBufferSize)?;
+        Sha256::finalize_into(self, out);
+        Ok(())
+    }
+
+    fn finalize_into_reset(&mut self, buf: &mut [u8]) -> Result<(), digest::InvalidBufferSize> {
+        let out: &mut [u8; 32] = buf.try_into().map_err(|_| digest::InvalidBufferSize)?;
+        *out = Sha256::finalize_reset(self);
+        Ok(())
+    }
+
+    fn reset(&mut self) { Sha256::reset(self); }
+
+    fn output_size(&self) -> usize { 32 }
+
+    fn box_clone(&self) -> alloc::boxed::Box<dyn digest::DynDigest> {
+        alloc::boxed::Box::new(self.clone())
+    }
+}
+
+impl Sha256 {
+    /// Export the running hash state so that hashing can be resumed later.
+    ///
+    /// Only states exported on a 64-byte boundary, i.e. after a total multiple of [`BLOCK_SIZE`]
+    /// bytes was absorbed, can be resumed with [`Sha256::from_state`].
+    ///
+    /// # Returns
+    ///
+    /// A snapshot of the hasher.
+    pub fn export_state(&self) -> Sha256State {
+        Sha256State {
+            state:      self.state,
+            buffer:     self.buffer,
+            buffer_len: self.buffer_len,
+            total_len:  self.total_len,
+        }
+    }
+
+    /// Resume hashing from a previously exported state.
+    ///
+    /// The state must have been exported on a 64-byte boundary. Such a checkpoint holds no
+    /// unprocessed message bytes, so a persisted state never contains raw input.
+    ///
+    /// # Parameters
+    ///
+    /// - `state`: State returned by [`Sha256::export_state`].
+    ///
+    /// # Returns
+    ///
+    /// A hasher that continues where the exported one left off, or
+    /// [`StateError::NotBlockAligned`] if the checkpoint was not taken on a block boundary.
+    pub fn from_state(state: Sha256State) -> Result<Self, StateError> {
+        if state.buffer_len != 0 || state.total_len % BLOCK_SIZE as u64 != 0 {
+            return Err(StateError::NotBlockAligned(state.total_len));
+        }
+        Ok(Self {
+            state:      state.state,
+            buffer:     state.buffer,
+            buffer_len: state.buffer_len,
+            total_len:  state.total_len,
+        })
+    }
+
+    /// Resume hashing from a raw midstate, e.g. eight words handed over by mining hardware.
+    ///
+    /// The low-level sibling of [`Sha256::from_state`]: nothing but the hash value and the length
+    /// are known, so the midstate must be taken on a block boundary.
+    ///
+    /// # Parameters
+    ///
+    /// - `state`: Intermediate hash value `H^(i)` after `bytes_already_hashed / 64` blocks.
+    /// - `bytes_already_hashed`: Number of message bytes compressed into `state`, counted in the
+    ///   length field of the final padding.
+    ///
+    /// # Returns
+    ///
+    /// A hasher continuing from `state`, or [`StateError::NotBlockAligned`] if
+    /// `bytes_already_hashed` is not a multiple of [`BLOCK_SIZE`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use shs_rs::sha256::{compress, sha256, Sha256, IHV};
+    ///
+    /// let mut midstate = IHV;
+    /// compress(&mut midstate, &[0x42; 64]);
+    ///
+    /// let mut hasher = Sha256::from_raw_state(midstate, 64)?;
+    /// hasher.update(b"tail");
+    /// assert_eq!(hasher.finalize(), sha256(&[&[0x42; 64][..], b"tail"].concat()));
+    /// # Ok::<(), shs_rs::sha256::StateError>(())
+    /// ```
+    pub fn from_raw_state(state: [u32; 8], bytes_already_hashed: u64) -> Result<Self, StateError> {
+        Self::from_state(Sha256State {
+            state,
+            buffer: [0; BLOCK_SIZE],
+            buffer_len: 0,
+            total_len: bytes_already_hashed,
+        })
+    }
+}
+
+/// Version of the [`Sha256State`] byte encoding.
+const STATE_VERSION: u8 = 1;
+
+/// Length in bytes of the [`Sha256State`] byte encoding.
+pub const STATE_LEN: usize = 1 + 32 + 8 + 1 + BLOCK_SIZE;
+
+/// Snapshot of a [`Sha256`] hasher, used to checkpoint and resume long computations.
+///
+/// Captures the running `[u32; 8]` hash value, the partial-block buffer, its length, and the
+/// total number of bytes absorbed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Sha256State {
+    state:      [u32; 8],
+    buffer:     [u8; BLOCK_SIZE],
+    buffer_len: usize,
+    total_len:  u64,
+}
+
+impl Sha256State {
+    /// Total number of message bytes absorbed before the snapshot was taken.
+    pub fn total_len(&self) -> u64 { self.total_len }
+
+    /// Encode the state into a stable byte layout.
+    ///
+    /// The layout is: version (1 byte), hash value (8 big-endian words), total length (64-bit
+    /// big-endian), buffer length (1 byte), and buffer (64 bytes).
+    ///
+    /// # Returns
+    ///
+    /// The encoded state.
+    pub fn to_bytes(&self) -> [u8; STATE_LEN] {
+        let mut bytes = [0u8; STATE_LEN];
+        bytes[0] = STATE_VERSION;
+        for (i, word) in self.state.iter().enumerate() {
+            bytes[1 + 4 * i..5 + 4 * i].copy_from_slice(&word.to_be_bytes());
+        }
+        bytes[33..41].copy_from_slice(&self.total_len.to_be_bytes());
+        bytes[41] = self.buffer_len as u8;
+        bytes[42..].copy_from_slice(&self.buffer);
+        bytes
+    }
+
+    /// Decode a state produced by [`Sha256State::to_bytes`].
+    ///
+    /// # Parameters
+    ///
+    /// - `bytes`: The encoded state.
+    ///
+    /// # Returns
+    ///
+    /// The decoded state, or an error if the version byte or buffer length is invalid.
+    pub fn from_bytes(bytes: &[u8; STATE_LEN]) -> Result<Self, StateError> {
+        if bytes[0] != STATE_VERSION {
+            return Err(StateError::UnsupportedVersion(bytes[0]));
+        }
+        let buffer_len = bytes[41] as usize;
+        if buffer_len >= BLOCK_SIZE {
+            return Err(StateError::InvalidBufferLength(buffer_len));
+        }
+
+        Ok(Self {
+            state: bytes_to_state(bytes[1..33].try_into().unwrap()),
+            buffer: bytes[42..].try_into().unwrap(),
+            buffer_len,
+            total_len: u64::from_be_bytes(bytes[33..41].try_into().unwrap()),
+        })
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Sha256State {
+    /// Serialize as the versioned [`Sha256State::to_bytes`] layout.
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(&self.to_bytes())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Sha256State {
+    /// Deserialize from the versioned [`Sha256State::to_bytes`] layout, rejecting unknown
+    /// versions.
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct StateVisitor;
+
+        impl<'de> serde::de::Visitor<'de> for StateVisitor {
+            type Value = Sha256State;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "{} bytes of SHA-256 state", STATE_LEN)
+            }
+
+            fn visit_bytes<E: serde::de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+                let bytes = v.try_into().map_err(|_| E::invalid_length(v.len(), &self))?;
+                Sha256State::from_bytes(bytes).map_err(E::custom)
+            }
+
+            fn visit_seq<A: serde::de::SeqAccess<'de>>(
+                self,
+                mut seq: A,
+            ) -> Result<Self::Value, A::Error> {
+                let mut bytes = [0u8; STATE_LEN];
+                for (i, byte) in bytes.iter_mut().enumerate() {
+                    *byte = seq
+                        .next_element()?
+                        .ok_or_else(|| serde::de::Error::invalid_length(i, &self))?;
+                }
+                if seq.next_element::<u8>()?.is_some() {
+                    return Err(serde::de::Error::invalid_length(STATE_LEN + 1, &self));
+                }
+                Sha256State::from_bytes(&bytes).map_err(serde::de::Error::custom)
+            }
+        }
+
+        deserializer.deserialize_bytes(StateVisitor)
+    }
+}
+
+/// Errors returned when decoding or resuming a [`Sha256State`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StateError {
+    /// The encoding uses a version this crate does not understand.
+    UnsupportedVersion(u8),
+    /// The partial-block buffer length is not smaller than [`BLOCK_SIZE`].
+    InvalidBufferLength(usize),
+    /// The state was exported after this many bytes, which is not a multiple of [`BLOCK_SIZE`].
+    NotBlockAligned(u64),
+}
+
+impl fmt::Display for StateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StateError::UnsupportedVersion(version) =>
+                write!(f, "unsupported state version {}, expected {}", version, STATE_VERSION),
+            StateError::InvalidBufferLength(len) =>
+                write!(f, "state buffer length {} exceeds maximum {}", len, BLOCK_SIZE - 1),
+            StateError::NotBlockAligned(len) =>
+                write!(f, "state exported after {} bytes, which is not block-aligned", len),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for StateError {}
+
+#[cfg(not(feature = "std"))]
+impl core::error::Error for StateError {}
+
+/// Divide a block of a buffer into sixteen big-endian words in a `const` context.
+///
+/// The `const` counterpart of `load_block`, which copies with a pointer.
+///
+/// See: FIPS 180-4, 3.1 and 6.2.2
+///
+/// # Parameters
+///
+/// - `data`: Buffer holding the block.
+/// - `offset`: Index of the first byte of the block in `data`.
+///
+/// # Returns
+///
+/// The words `M_0^(i)..M_15^(i)`.
+const fn load_block_const(data: &[u8], offset: usize) -> [u32; 16] {
+    let mut words = [0u32; 16];
+    let mut t = 0;
+    while t < 16 {
+        let i = offset + 4 * t;
+        words[t] = u32::from_be_bytes([data[i], data[i + 1], data[i + 2], data[i + 3]]);
+        t += 1;
+    }
+    words
+}
+
+/// Compute SHA-256 digest of a message in a `const` context.
+///
+/// Unlike [`sha256`], this function does not allocate: full blocks are read directly from
+/// `message` and the padded final block(s) are assembled in a stack buffer.
+///
+/// # Parameters
+///
+/// - `message`: Input message to hash.
+///
+/// # Returns
+///
+/// 256-bit digest of the `message`.
+///
+/// # Examples
+///
+/// ```
+/// use shs_rs::sha256::{sha256, sha256_const};
+///
+/// const DOMAIN: [u8; 32] = sha256_const(b"myapp-v1");
+/// assert_eq!(DOMAIN, sha256(b"myapp-v1"));
+/// ```
+#[must_use]
+pub const fn sha256_const(message: &[u8]) -> [u8; 32] {
+    let mut state = IHV;
+
+    // Process all complete blocks in place
+    let full_blocks = message.len() / 64;
+    let mut i = 0;
+    while i < full_blocks {
+        state = compress_const(state, load_block_const(message, i * 64));
+        i += 1;
+    }
+
+    // Pad the remaining bytes into one or two blocks: FIPS 180-4, 5.1.1
+    let (tail, tail_blocks) = final_blocks(message);
+
+    i = 0;
+    while i < tail_blocks {
+        state = compress_const(state, load_block_const(&tail, i * 64));
+        i += 1;
+    }
+
+    // Final digest
+    let mut result = [0u8; 32];
+    i = 0;
+    while i < 8 {
+        let word = state[i].to_be_bytes();
+        let mut j = 0;
+        while j < 4 {
+            result[i * 4 + j] = word[j];
+            j += 1;
+        }
+        i += 1;
+    }
+    result
+}
+
+// Known-answer tests of `sha256_const`, evaluated at compile time so a broken `const` path fails
+// the build: an empty message, one block, and two blocks of padding.
+//
+// See: NIST CSRC Example Values, SHA-256
+const _: () = {
+    /// Compare two digests, `==` on arrays is not available in a `const` context.
+    const fn digest_eq(a: &[u8; 32], b: &[u8; 32]) -> bool {
+        let mut i = 0;
+        while i < 32 {
+            if a[i] != b[i] {
+                return false;
+            }
+            i += 1;
+        }
+        true
+    }
+
+    assert!(digest_eq(&sha256_const(b""), &[
+        0xe3, 0xb0, 0xc4, 0x42, 0x98, 0xfc, 0x1c, 0x14, 0x9a, 0xfb, 0xf4, 0xc8, 0x99, 0x6f, 0xb9,
+        0x24, 0x27, 0xae, 0x41, 0xe4, 0x64, 0x9b, 0x93, 0x4c, 0xa4, 0x95, 0x99, 0x1b, 0x78, 0x52,
+        0xb8, 0x55,
+    ]));
+    assert!(digest_eq(&sha256_const(b"abc"), &[
+        0xba, 0x78, 0x16, 0xbf, 0x8f, 0x01, 0xcf, 0xea, 0x41, 0x41, 0x40, 0xde, 0x5d, 0xae, 0x22,
+        0x23, 0xb0, 0x03, 0x61, 0xa3, 0x96, 0x17, 0x7a, 0x9c, 0xb4, 0x10, 0xff, 0x61, 0xf2, 0x00,
+        0x15, 0xad,
+    ]));
+    assert!(digest_eq(
+        &sha256_const(b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq"),
+        &[
+            0x24, 0x8d, 0x6a, 0x61, 0xd2, 0x06, 0x38, 0xb8, 0xe5, 0xc0, 0x26, 0x93, 0x0c, 0x3e,
+            0x60, 0x39, 0xa3, 0x3c, 0xe4, 0x59, 0x64, 0xff, 0x21, 0x67, 0xf6, 0xec, 0xed, 0xd4,
+            0x19, 0xdb, 0x06, 0xc1,
+        ]
+    ));
+};
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_rotr() {
+        assert_eq!(rotr::<0>(0x12345678), 0x12345678);
+        assert_eq!(rotr::<4>(0x12345678), 0x81234567);
+        assert_eq!(rotr::<8>(0x12345678), 0x78123456);
+        assert_eq!(rotr::<16>(0x12345678), 0x56781234);
+        assert_eq!(rotr::<24>(0x12345678), 0x34567812);
+        assert_eq!(rotr::<31>(0x12345678), 0x2468acf0);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_padding() {
+        // (input, expected_output)
+        let test_vectors = [
+            (vec![0x61], [vec![0x61, 0x80], vec![0; 61], vec![8]].concat()),
+            (vec![0x61, 0x62], [vec![0x61, 0x62, 0x80], vec![0; 60], vec![16]].concat()),
+            (
+                [vec![0x61, 0x62], vec![0; 64]].concat(),
+                [vec![0x61, 0x62], vec![0; 64], vec![128], vec![0; 59], vec![2, 16]].concat(),
+            ),
+        ];
+
+        // Around the 56-byte boundary past which the length no longer fits the same block
+        for (len, padded_len) in [(54, 64), (55, 64), (56, 128), (57, 128), (119, 128), (120, 192)]
+        {
+            let input = vec![0x61; len];
+            let output = padding(&input);
+            assert_eq!(output.len(), padded_len, "Length {} failed", len);
+            assert_eq!(output[..len], input[..]);
+            assert_eq!(output[len], 0x80);
+            assert!(output[len + 1..padded_len - 8].iter().all(|&byte| byte == 0));
+            assert_eq!(output[padded_len - 8..], ((len as u64) * 8).to_be_bytes());
+        }
+
+        for (input, expected) in test_vectors.into_iter() {
+            let input = input.clone();
+            let output = padding(&input);
+            assert_eq!(output.len() % 64, 0);
+            assert_eq!(output.len(), expected.len());
+            assert_eq!(output.to_vec(), expected);
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_padding_into() {
+        // The stack-buffer padding must agree with the tail of the allocating one
+        for len in [0, 1, 55, 56, 63, 64, 65, 119, 120, 1000] {
+            let message: Vec<u8> = (0..len).map(|i| i as u8).collect();
+            let mut tail = [0u8; 128];
+            let tail_blocks = padding_into(&message, &mut tail);
+            assert_eq!(tail_blocks, if len % 64 < 56 { 1 } else { 2 });
+
+            let padded = padding(&message);
+            assert_eq!(tail[..tail_blocks * 64], padded[len - len % 64..], "Length {} failed", len);
+        }
+    }
+
+    #[test]
+    fn test_initial_hash_values() {
+        // Checks whether `IHV` vector contains correct values as per FIPS.
+
+        // The first 8 prime numbers
+        let primes: [u32; 8] = [2, 3, 5, 7, 11, 13, 17, 19];
+        let generated_ihv: Vec<u32> = primes
+            .into_iter()
+            .map(|prime| {
+                // Calculate the square root and its fractional part
+                let sqrt_fractional = (prime as f64).sqrt() - (prime as f64).sqrt().floor();
+                // Convert the fractional part to a 32-bit word
+                (sqrt_fractional * (1_u64 << 32) as f64) as u32
+            })
+            .collect();
+        let generated_ihv: [u32; 8] = generated_ihv.try_into().unwrap();
+
+        assert_eq!(IHV, generated_ihv);
+    }
+
+    #[test]
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64", target_arch = "aarch64"))]
+    fn test_words_k_grouped() {
+        assert_eq!(WORDS_K_GROUPED.concat(), WORDS_K);
+        assert_eq!(WORDS_K_GROUPED[0], [0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5]);
+        assert_eq!(WORDS_K_GROUPED[15], [0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2]);
+    }
+
+    #[test]
+    fn test_words_k() {
+        // Checks whether `WORDS_K` vector contains correct values as per FIPS.
+
+        // The first 64 prime numbers
+        let primes: [u32; 64] = [
+            2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61, 67, 71, 73, 79, 83,
+            89, 97, 101, 103, 107, 109, 113, 127, 131, 137, 139, 149, 151, 157, 163, 167, 173, 179,
+            181, 191, 193, 197, 199, 211, 223, 227, 229, 233, 239, 241, 251, 257, 263, 269, 271,
+            277, 281, 283, 293, 307, 311,
         ];
         let generated_words_k: Vec<u32> = primes
             .into_iter()
@@ -321,50 +2040,1105 @@ mod test {
     }
 
     #[test]
-    fn test_sha256() {
-        let test_cases = [
-            ("", "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"),
-            ("abc", "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"),
-            (
-                "abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq",
-                "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1",
-            ),
-        ];
+    fn test_sha256() {
+        let test_cases = [
+            ("", "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"),
+            ("abc", "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"),
+            (
+                "abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq",
+                "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1",
+            ),
+        ];
+
+        for (input, expected) in test_cases.iter() {
+            let result = sha256(input.as_bytes());
+            assert_eq!(hex::encode(result), *expected);
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_compress() {
+        // FIPS 180-2, Appendix B.1: "abc" is a single padded block
+        let padded = padding(b"abc");
+        let mut state = IHV;
+        compress(&mut state, padded.as_slice().try_into().unwrap());
+        assert_eq!(state, [
+            0xba7816bf, 0x8f01cfea, 0x414140de, 0x5dae2223, 0xb00361a3, 0x96177a9c, 0xb410ff61,
+            0xf20015ad,
+        ]);
+    }
+
+    #[test]
+    #[cfg(feature = "zeroize")]
+    fn test_sha256_consume() {
+        for len in [0, 1, 64, 100] {
+            let original: Vec<u8> = (1..=len).map(|i| i as u8).collect();
+            let mut data = original.clone();
+            assert_eq!(sha256_consume(&mut data), sha256(&original));
+            assert!(data.iter().all(|&byte| byte == 0));
+        }
+    }
+
+    #[test]
+    fn test_sha256_select() {
+        assert_eq!(sha256_select(Choice::from(0), b"left", b"rght"), sha256(b"left"));
+        assert_eq!(sha256_select(Choice::from(1), b"left", b"rght"), sha256(b"rght"));
+
+        // The other message has no influence on the result
+        for other in [&b""[..], b"left", b"other message", &[0xff; 100]] {
+            assert_eq!(sha256_select(Choice::from(0), b"left", other), sha256(b"left"));
+            assert_eq!(sha256_select(Choice::from(1), other, b"rght"), sha256(b"rght"));
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_sha256_fixed_size() {
+        use rand::Rng;
+
+        let mut rng = crate::test_rng::test_rng();
+        for _ in 0..10 {
+            let short: [u8; 32] = rng.gen();
+            let mut long = [0u8; 64];
+            rng.fill(&mut long);
+            assert_eq!(sha256_block32(&short), sha256(&short));
+            assert_eq!(sha256_block64(&long), sha256(&long));
+        }
+        assert_eq!(PADDING_BLOCK64[..], padding(&[0u8; 64])[64..]);
+    }
+
+    #[test]
+    fn test_sha256_repeated() {
+        // FIPS 180-4 example: one million 'a'
+        assert_eq!(
+            hex::encode(sha256_repeated(b'a', 1_000_000)),
+            "cdc76e5c9914fb9281a1c7e284d73e67f1809a48a497200e046d39ccc7112cd0"
+        );
+        for count in [0, 1, 55, 56, 64, 1023, 1024, 1025, 5000] {
+            assert_eq!(sha256_repeated(0x5c, count), sha256(&vec![0x5c; count]));
+        }
+    }
+
+    #[test]
+    fn test_empty_message() {
+        // Only padding, no data: a single block that is 0x80 followed by zeros
+        const EMPTY: [u8; 32] = sha256_const(b"");
+        let expected =
+            hex_to_bytes("e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855");
+        assert_eq!(EMPTY[..], expected);
+        assert_eq!(sha256(b"")[..], expected);
+        assert_eq!(sha256_with_iv(IHV, b"")[..], expected);
+        assert_eq!(sha256_with_params(&Sha256Params::STANDARD, b"")[..], expected);
+        assert_eq!(sha256_vectored(&[])[..], expected);
+        assert_eq!(sha256_repeated(0, 0)[..], expected);
+        assert_eq!(
+            sha256_x4([b""; 4]).map(|digest| digest.to_vec()),
+            [(); 4].map(|_| expected.clone())
+        );
+
+        let mut tail = [0xffu8; 128];
+        assert_eq!(padding_into(b"", &mut tail), 1);
+        assert_eq!(tail[0], 0x80);
+        assert!(tail[1..64].iter().all(|&byte| byte == 0));
+        for backend in Backend::ALL.into_iter().filter(|backend| backend.is_available()) {
+            let digest = compute_hash_with_backend(backend, IHV, &tail[..64]).unwrap();
+            assert_eq!(digest[..], expected, "{} failed", backend);
+        }
+
+        // Streaming, finalized right away or after updates without data
+        assert_eq!(Sha256::new().finalize()[..], expected);
+        assert_eq!(state_to_bytes(&Sha256::new().finalize_words())[..], expected);
+        let mut hasher = Sha256::new();
+        hasher.update(b"");
+        hasher.update_iter(core::iter::empty());
+        assert_eq!(hasher.finalize_reset()[..], expected);
+        assert_eq!(hasher.finalize()[..], expected);
+    }
+
+    #[test]
+    fn test_sha256_of() {
+        let message = "Hello, world!";
+        assert_eq!(sha256_of(message), sha256(message.as_bytes()));
+        let owned = String::from(message);
+        assert_eq!(sha256_of(&owned), sha256(message.as_bytes()));
+        assert_eq!(sha256_of(owned.into_bytes()), sha256(message.as_bytes()));
+        assert_eq!(sha256_of([0u8; 4]), sha256(&[0u8; 4]));
+        assert_eq!(sha256_of(Digest(sha256(b"abc"))), sha256d(b"abc"));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_sha256_with_iv() {
+        for len in [0, 1, 55, 56, 63, 64, 65, 119, 120, 1000] {
+            let message: Vec<u8> = (0..len).map(|i| i as u8).collect();
+            assert_eq!(sha256_with_iv(IHV, &message), sha256(&message));
+        }
+
+        // Chaining from the state after one block matches hashing the whole message except for
+        // the length field, which only counts the bytes hashed from `iv`
+        let mut state = IHV;
+        compress(&mut state, &[0x61; 64]);
+        let mut hasher = Sha256::new();
+        hasher.update(&[0x61; 64]);
+        hasher.update(b"abc");
+        assert_ne!(sha256_with_iv(state, b"abc"), hasher.finalize());
+        assert_eq!(sha256_with_iv(state, b"abc"), compute_hash_bytes(state, &padding(b"abc")));
+    }
+
+    #[test]
+    fn test_load_block() {
+        let block: Vec<u8> = (0..70).collect();
+        let words = load_block(block[..BLOCK_SIZE].try_into().unwrap());
+        for (t, word) in words.iter().enumerate() {
+            let bytes = [block[4 * t], block[4 * t + 1], block[4 * t + 2], block[4 * t + 3]];
+            assert_eq!(*word, u32::from_be_bytes(bytes));
+
+            // The copy leaves the native-endian reading in each word, which `from_be` swaps on
+            // little-endian hosts and keeps on big-endian ones
+            let native = u32::from_ne_bytes(bytes);
+            assert_eq!(u32::from_be(native), *word);
+            #[cfg(target_endian = "little")]
+            assert_eq!(native.swap_bytes(), *word);
+            #[cfg(target_endian = "big")]
+            assert_eq!(native, *word);
+        }
+        assert_eq!(words[0], 0x00010203);
+        assert_eq!(words[15], 0x3c3d3e3f);
+
+        // The `const` load agrees, at any offset into the buffer
+        assert_eq!(load_block_const(&block, 0), words);
+        assert_eq!(load_block_const(&block, 6)[0], 0x06070809);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_message_schedule() {
+        let padded = padding(b"abc");
+        let w = message_schedule(padded.as_slice().try_into().unwrap());
+
+        // FIPS 180-4 example intermediate values for "abc"
+        assert_eq!(w[0], 0x61626380);
+        assert_eq!(w[15], 0x00000018);
+        assert_eq!(w[16], 0x61626380);
+        assert_eq!(w[63], 0x12b1edeb);
+    }
+
+    #[test]
+    #[should_panic(expected = "message blocks must be 64 bytes")]
+    fn test_compute_hash_short_block() { let _ = compute_hash(IHV, &[&[0u8; 63]]); }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_try_compute_hash() {
+        let padded = padding(b"abc");
+        assert_eq!(try_compute_hash(IHV, &[&padded]), Ok(sha256(b"abc")));
+        assert_eq!(try_compute_hash(IHV, &[]), Ok(compute_hash(IHV, &[])));
+        assert_eq!(
+            try_compute_hash(IHV, &[&padded, &padded[1..], &[]]),
+            Err(ShaError::InvalidBlockLength { index: 1, len: 63 })
+        );
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_compute_hash_bytes() {
+        for len in [0, 1, 55, 56, 64, 1000] {
+            let message: Vec<u8> = (0..len).map(|i| i as u8).collect();
+            let padded = padding(&message);
+            let blocks: Vec<&[u8]> = padded.chunks_exact(64).collect();
+            assert_eq!(compute_hash_bytes(IHV, &padded), compute_hash(IHV, &blocks));
+            assert_eq!(compute_hash_bytes(IHV, &padded), sha256(&message));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "complete 512-bit blocks")]
+    fn test_compute_hash_bytes_partial_block() { let _ = compute_hash_bytes(IHV, &[0u8; 65]); }
+
+    fn hex_to_bytes(s: &str) -> Vec<u8> {
+        (0..s.len()).step_by(2).map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap()).collect()
+    }
+
+    /// (name, hex-encoded input, expected digest)
+    const NIST_VECTORS: [(&str, &str, &str); 3] = [
+        (
+            "NIST.1",
+            "616263",
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad",
+        ),
+        (
+            "NIST.2",
+            "6162636462636465636465666465666765666768666768696768696a68696a6b696a6b6c6a6b6c6d6b6c6d6e6c6d6e6f6d6e6f706e6f7071",
+            "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1",
+        ),
+        (
+            "EMPTY",
+            "",
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+        ),
+    ];
+
+    #[test]
+    fn test_sha256_vectors() {
+        for (name, input, expected) in NIST_VECTORS.iter() {
+            let input_bytes = hex_to_bytes(input);
+            let result = sha256(&input_bytes);
+            assert_eq!(hex::encode(result), *expected, "Test vector '{}' failed", name);
+        }
+    }
+
+    /// Signature shared by all compression backends.
+    type CompressFn = unsafe fn(&mut [u32; 8], &[u8]);
+
+    /// Hash `message` with a specific compression backend.
+    fn sha256_with(message: &[u8], compress: CompressFn) -> [u8; 32] {
+        let mut hash_value = IHV;
+        let mut tail = [0u8; 128];
+        let tail_blocks = padding_into(message, &mut tail);
+        let whole_blocks = message.len() / BLOCK_SIZE * BLOCK_SIZE;
+        unsafe {
+            compress(&mut hash_value, &message[..whole_blocks]);
+            compress(&mut hash_value, &tail[..tail_blocks * BLOCK_SIZE]);
+        }
+        state_to_bytes(&hash_value)
+    }
+
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    #[test]
+    fn test_sha_ni_matches_portable() {
+        if !x86::sha_ni_available() {
+            // Nothing to compare against on this CPU
+            return;
+        }
+
+        for (name, input, expected) in NIST_VECTORS.iter() {
+            let input_bytes = hex_to_bytes(input);
+            let backends: [CompressFn; 2] = [compress_blocks_portable, x86::compress_blocks_sha_ni];
+            for compress in backends {
+                let result = sha256_with(&input_bytes, compress);
+                assert_eq!(hex::encode(result), *expected, "Test vector '{}' failed", name);
+            }
+        }
+
+        // Every padding case over several blocks
+        for len in 0..=300 {
+            let message: Vec<u8> = (0..len).map(|i| i as u8).collect();
+            assert_eq!(
+                sha256_with(&message, x86::compress_blocks_sha_ni),
+                sha256_with(&message, compress_blocks_portable),
+                "Length {} failed",
+                len
+            );
+        }
+    }
+
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    #[test]
+    fn test_avx2_schedule_matches_portable() {
+        use rand::Rng;
 
-        for (input, expected) in test_cases.iter() {
-            let result = sha256(input.as_bytes());
-            assert_eq!(hex::encode(result), *expected);
+        if !x86::avx2_available() {
+            // Nothing to compare against on this CPU
+            return;
+        }
+
+        let mut rng = crate::test_rng::test_rng();
+        for _ in 0..256 {
+            let (mut first, mut second) = ([0u8; 64], [0u8; 64]);
+            rng.fill(&mut first);
+            rng.fill(&mut second);
+            let [w_first, w_second] = unsafe { x86::schedule_avx2(&first, &second) };
+            assert_eq!(w_first, schedule(&first));
+            assert_eq!(w_second, schedule(&second));
+        }
+
+        // Both even and odd numbers of blocks
+        for len in 0..=300 {
+            let message: Vec<u8> = (0..len).map(|_| rng.gen()).collect();
+            assert_eq!(
+                sha256_with(&message, x86::compress_blocks_avx2),
+                sha256_with(&message, compress_blocks_portable),
+                "Length {} failed",
+                len
+            );
         }
     }
 
-    fn hex_to_bytes(s: &str) -> Vec<u8> {
-        (0..s.len()).step_by(2).map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap()).collect()
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    #[test]
+    fn test_sse2_schedule_matches_portable() {
+        use rand::Rng;
+
+        if !x86::sse2_available() {
+            // Nothing to compare against on this CPU
+            return;
+        }
+
+        let mut rng = crate::test_rng::test_rng();
+        for _ in 0..256 {
+            let mut block = [0u8; 64];
+            rng.fill(&mut block);
+            assert_eq!(unsafe { x86::schedule_sse2(&block) }, schedule(&block));
+        }
+
+        for len in 0..=300 {
+            let message: Vec<u8> = (0..len).map(|_| rng.gen()).collect();
+            assert_eq!(
+                sha256_with(&message, x86::compress_blocks_sse2),
+                sha256_with(&message, compress_blocks_portable),
+                "Length {} failed",
+                len
+            );
+        }
     }
 
+    #[cfg(target_arch = "aarch64")]
     #[test]
-    fn test_sha256_vectors() {
-        let test_vectors = [
-            (
-                "NIST.1",
-                "616263",
-                "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
-            ),
-            (
-                "NIST.2",
-                "6162636462636465636465666465666765666768666768696768696a68696a6b696a6b6c6a6b6c6d6b6c6d6e6c6d6e6f6d6e6f706e6f7071",
-                "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1"
-            ),
-            (
-                "EMPTY",
-                "",
-                "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
-            ),
-        ];
+    fn test_neon_matches_portable() {
+        use rand::Rng;
+
+        if !aarch64::sha2_available() {
+            // Nothing to compare against on this CPU
+            return;
+        }
 
-        for (name, input, expected) in test_vectors.iter() {
+        let mut rng = crate::test_rng::test_rng();
+        for blocks in 0..=16 {
+            for _ in 0..8 {
+                let len = blocks * BLOCK_SIZE + rng.gen_range(0..BLOCK_SIZE);
+                let message: Vec<u8> = (0..len).map(|_| rng.gen()).collect();
+                assert_eq!(
+                    sha256_with(&message, aarch64::compress_blocks_neon),
+                    sha256_with(&message, compress_blocks_portable),
+                    "Length {} failed",
+                    len
+                );
+            }
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_sha256_hex() {
+        for (name, input, expected) in NIST_VECTORS.iter() {
             let input_bytes = hex_to_bytes(input);
-            let result = sha256(&input_bytes);
-            assert_eq!(hex::encode(result), *expected, "Test vector '{}' failed", name);
+            assert_eq!(sha256_hex(&input_bytes), *expected, "Test vector '{}' failed", name);
+            assert_eq!(sha256_hex_upper(&input_bytes), expected.to_uppercase());
+        }
+    }
+
+    #[cfg(feature = "base64")]
+    #[test]
+    fn test_sha256_base64() {
+        assert_eq!(sha256_base64(b""), "47DEQpj8HBSa+/TImW+5JCeuQeRkm5NMpJWZG3hSuFU=");
+        assert_eq!(sha256_base64(b"abc"), "ungWv48Bz+pBQUDeXa4iI7ADYaOWF3qctBD/YfIAFa0=");
+    }
+
+    #[test]
+    fn test_sha256d() {
+        for len in [0, 1, 32, 55, 56, 64, 1000] {
+            let message: Vec<u8> = (0..len).map(|i| i as u8).collect();
+            assert_eq!(sha256d(&message), sha256(&sha256(&message)), "Length {} failed", len);
+        }
+    }
+
+    #[test]
+    fn test_sha256_vectored() {
+        assert_eq!(sha256_vectored(&[b"ab", b"c"]), sha256(b"abc"));
+        assert_eq!(sha256_vectored(&[b"", b"ab", b"", b"c", b""]), sha256(b"abc"));
+        assert_eq!(sha256_vectored(&[]), sha256(b""));
+
+        // Pieces straddling block boundaries
+        let message: Vec<u8> = (0..1000).map(|i| i as u8).collect();
+        let (a, rest) = message.split_at(63);
+        let (b, c) = rest.split_at(130);
+        assert_eq!(sha256_vectored(&[a, b, c]), sha256(&message));
+    }
+
+    #[test]
+    fn test_unaligned_input() {
+        // Slices starting at every offset from a 16-byte aligned address
+        #[repr(align(16))]
+        struct Aligned([u8; 1040]);
+        let mut buffer = Aligned([0; 1040]);
+        for (i, byte) in buffer.0.iter_mut().enumerate() {
+            *byte = (i * 7) as u8;
+        }
+
+        for offset in 0..16 {
+            let message = &buffer.0[offset..offset + 1024];
+            let expected: [u8; 32] = <sha2::Sha256 as sha2::Digest>::digest(message).into();
+            assert_eq!(sha256(message), expected, "offset {}", offset);
+            assert_eq!(Sha256::from(&message[..100]).chain(&message[100..]).finalize(), expected);
+            assert_eq!(sha256_x4([message; 4]), [expected; 4]);
+
+            // Every backend on whole blocks, read without going through a buffer first
+            for backend in Backend::ALL.into_iter().filter(|backend| backend.is_available()) {
+                let digest = compute_hash_with_backend(backend, IHV, message).unwrap();
+                let mut hash_value = IHV;
+                compress_blocks_portable(&mut hash_value, message);
+                assert_eq!(digest, state_to_bytes(&hash_value), "{} at offset {}", backend, offset);
+            }
+        }
+    }
+
+    #[test]
+    fn test_sha256_nostd() {
+        for (name, input, expected) in NIST_VECTORS.iter() {
+            let digest = sha256_nostd(&hex::decode(input).unwrap());
+            assert_eq!(hex::encode(digest), *expected, "{} failed", name);
+        }
+        // Remainders on both sides of the one or two padding blocks split
+        for len in 0..=200 {
+            let message: Vec<u8> = (0..len).map(|i| i as u8).collect();
+            assert_eq!(sha256_nostd(&message), sha256(&message), "Length {} failed", len);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "unicode")]
+    fn test_sha256_str_nfc() {
+        let (precomposed, decomposed) = ("\u{e9}", "e\u{301}");
+        assert_ne!(sha256(precomposed.as_bytes()), sha256(decomposed.as_bytes()));
+        assert_eq!(sha256_str_nfc(precomposed), sha256_str_nfc(decomposed));
+        assert_eq!(sha256_str_nfc(decomposed), sha256("\u{e9}".as_bytes()));
+
+        // Already normalized text, including ASCII, hashes as its bytes
+        for s in ["", "abc", "Zo\u{eb}", "\u{1f600} \u{d55c}"] {
+            assert_eq!(sha256_str_nfc(s), sha256(s.as_bytes()));
+        }
+        // Hangul jamo compose into syllables, combining marks get reordered
+        assert_eq!(sha256_str_nfc("\u{1112}\u{1161}\u{11ab}"), sha256("\u{d55c}".as_bytes()));
+        assert_eq!(sha256_str_nfc("a\u{323}\u{301}"), sha256_str_nfc("a\u{301}\u{323}"));
+    }
+
+    #[test]
+    fn test_sha256_with_mask() {
+        let message: Vec<u8> = (0..300).map(|i| (i as u8) | 1).collect();
+        for len in [0, 1, 63, 64, 65, 300] {
+            let message = &message[..len];
+            let bounds = [0, 1, 32, 63, 64, 65, 128, 299, 300];
+            for &start in bounds.iter().filter(|&&start| start <= len) {
+                for &end in bounds.iter().filter(|&&end| start <= end && end <= len) {
+                    let mut zeroed = message.to_vec();
+                    zeroed[start..end].fill(0);
+                    assert_eq!(
+                        sha256_with_mask(message, start..end),
+                        sha256(&zeroed),
+                        "len {} range {}..{}",
+                        len,
+                        start,
+                        end
+                    );
+                }
+            }
+        }
+
+        // Empty and full ranges
+        assert_eq!(sha256_with_mask(&message, 17..17), sha256(&message));
+        assert_eq!(sha256_with_mask(&message, 0..300), sha256(&[0; 300]));
+    }
+
+    #[test]
+    #[should_panic(expected = "out of bounds")]
+    fn test_sha256_with_mask_out_of_bounds() { let _ = sha256_with_mask(b"abc", 2..4); }
+
+    #[test]
+    fn test_sha256_chunks() {
+        assert_eq!(sha256_chunks(["a", "b", "c"].map(str::as_bytes)), sha256(b"abc"));
+        assert_eq!(sha256_chunks(core::iter::empty()), sha256(b""));
+
+        // Lazily produced pieces straddling block boundaries
+        let message: Vec<u8> = (0..1000).map(|i| i as u8).collect();
+        let chunks = [0, 1, 63, 64, 65, 130].iter().scan(0, |start, &len| {
+            let chunk = &message[*start..*start + len];
+            *start += len;
+            Some(chunk)
+        });
+        assert_eq!(sha256_chunks(chunks.chain([&message[323..]])), sha256(&message));
+        assert_eq!(sha256_chunks(message.chunks(7)), sha256(&message));
+    }
+
+    #[test]
+    fn test_sha256_truncated() {
+        let digest = hex::decode(NIST_VECTORS[0].2).unwrap();
+        assert_eq!(sha256_truncated::<16>(b"abc")[..], digest[..16]);
+        assert_eq!(sha256_truncated::<20>(b"abc")[..], digest[..20]);
+        assert_eq!(sha256_truncated::<32>(b"abc")[..], digest[..]);
+        assert_eq!(sha256_truncated::<0>(b"abc"), [0u8; 0]);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_sha256_batch() {
+        let messages: Vec<Vec<u8>> =
+            (0..1000).map(|len| (0..len).map(|i| i as u8).collect()).collect();
+        let slices: Vec<&[u8]> = messages.iter().map(Vec::as_slice).collect();
+
+        let digests = sha256_batch(&slices);
+        assert_eq!(digests.len(), messages.len());
+        for (message, digest) in messages.iter().zip(digests) {
+            assert_eq!(digest, sha256(message), "Length {} failed", message.len());
+        }
+        assert!(sha256_batch(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_sha256_const() {
+        const DOMAIN: [u8; 32] = sha256_const(b"myapp-v1");
+        assert_eq!(DOMAIN, sha256(b"myapp-v1"));
+
+        // Cover both one- and two-block padding, and multi-block messages
+        for len in [0, 1, 55, 56, 63, 64, 65, 119, 120, 128, 1000] {
+            let message: Vec<u8> = (0..len).map(|i| i as u8).collect();
+            assert_eq!(sha256_const(&message), sha256(&message), "Length {} failed", len);
+        }
+    }
+
+    #[test]
+    fn test_verify() {
+        let digest = sha256(b"abc");
+        assert!(bool::from(verify(&digest, &sha256(b"abc"))));
+
+        // Mismatch in the first, a middle, and the last byte
+        for i in [0, 15, 31] {
+            let mut other = digest;
+            other[i] ^= 1;
+            assert!(!bool::from(verify(&digest, &other)), "Byte {} not detected", i);
+        }
+    }
+
+    #[test]
+    fn test_resume_from_state() {
+        let message: Vec<u8> = (0..1024).map(|i| i as u8).collect();
+        let (head, tail) = message.split_at(8 * BLOCK_SIZE);
+
+        let mut hasher = Sha256::new();
+        hasher.update(head);
+        let bytes = hasher.export_state().to_bytes();
+
+        let state = Sha256State::from_bytes(&bytes).unwrap();
+        assert_eq!(state.total_len(), head.len() as u64);
+        let mut resumed = Sha256::from_state(state).unwrap();
+        resumed.update(tail);
+        assert_eq!(resumed.finalize(), sha256(&message));
+    }
+
+    #[test]
+    fn test_resume_requires_block_boundary() {
+        let mut hasher = Sha256::new();
+        hasher.update(&[0u8; BLOCK_SIZE + 1]);
+        let state = hasher.export_state();
+        assert_eq!(
+            Sha256::from_state(state).err(),
+            Some(StateError::NotBlockAligned(BLOCK_SIZE as u64 + 1))
+        );
+    }
+
+    #[test]
+    fn test_from_raw_state() {
+        let message: Vec<u8> = (0..128).map(|i| i as u8).collect();
+        let mut hasher = Sha256::new();
+        hasher.update(&message[..64]);
+        let midstate = hasher.export_state().state;
+
+        let mut resumed = Sha256::from_raw_state(midstate, 64).unwrap();
+        resumed.update(&message[64..]);
+        assert_eq!(resumed.finalize(), sha256(&message));
+
+        // The initial hash value is the raw state of the empty message
+        let mut fresh = Sha256::from_raw_state(IHV, 0).unwrap();
+        fresh.update(&message);
+        assert_eq!(fresh.finalize(), sha256(&message));
+
+        assert_eq!(
+            Sha256::from_raw_state(midstate, 65).err(),
+            Some(StateError::NotBlockAligned(65))
+        );
+    }
+
+    #[test]
+    fn test_state_from_invalid_bytes() {
+        let mut bytes = Sha256::new().export_state().to_bytes();
+        bytes[0] = 0xff;
+        assert_eq!(Sha256State::from_bytes(&bytes), Err(StateError::UnsupportedVersion(0xff)));
+
+        let mut bytes = Sha256::new().export_state().to_bytes();
+        bytes[41] = BLOCK_SIZE as u8;
+        assert_eq!(
+            Sha256State::from_bytes(&bytes),
+            Err(StateError::InvalidBufferLength(BLOCK_SIZE))
+        );
+    }
+
+    #[cfg(feature = "digest")]
+    #[test]
+    fn test_dyn_digest() {
+        use digest::DynDigest;
+
+        let mut hasher: Box<dyn DynDigest> = Box::new(Sha256::new());
+        hasher.update(b"ab");
+        let copy = hasher.box_clone();
+        hasher.update(b"c");
+        assert_eq!(hasher.output_size(), 32);
+        assert_eq!(hasher.finalize_reset()[..], sha256(b"abc"));
+        assert_eq!(hasher.finalize()[..], sha256(b""));
+        assert_eq!(copy.finalize()[..], sha256(b"ab"));
+
+        let mut short = [0u8; 31];
+        let mut hasher = Sha256::new();
+        assert!(DynDigest::finalize_into_reset(&mut hasher, &mut short).is_err());
+        let mut out = [0u8; 32];
+        DynDigest::update(&mut hasher, b"abc");
+        DynDigest::finalize_into(hasher, &mut out).unwrap();
+        assert_eq!(out, sha256(b"abc"));
+    }
+
+    #[cfg(feature = "serde")]
+    fn checkpoint_at_block_boundary() -> (Vec<u8>, Sha256State) {
+        let message: Vec<u8> = (0..1024).map(|i| i as u8).collect();
+        let mut hasher = Sha256::new();
+        hasher.update(&message[..4 * BLOCK_SIZE]);
+        (message, hasher.export_state())
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_state_serde_json() {
+        let (message, state) = checkpoint_at_block_boundary();
+        let json = serde_json::to_string(&state).unwrap();
+        let deserialized: Sha256State = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized, state);
+
+        let mut resumed = Sha256::from_state(deserialized).unwrap();
+        resumed.update(&message[4 * BLOCK_SIZE..]);
+        assert_eq!(resumed.finalize(), sha256(&message));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_state_serde_bincode() {
+        let (message, state) = checkpoint_at_block_boundary();
+        let encoded = bincode::serialize(&state).unwrap();
+        let deserialized: Sha256State = bincode::deserialize(&encoded).unwrap();
+        assert_eq!(deserialized, state);
+
+        let mut resumed = Sha256::from_state(deserialized).unwrap();
+        resumed.update(&message[4 * BLOCK_SIZE..]);
+        assert_eq!(resumed.finalize(), sha256(&message));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_state_serde_version_mismatch() {
+        let (_, state) = checkpoint_at_block_boundary();
+        let mut encoded = bincode::serialize(&state).unwrap();
+        // Skip the 8-byte length prefix bincode puts in front of a byte string
+        encoded[8] = STATE_VERSION + 1;
+        let err = bincode::deserialize::<Sha256State>(&encoded).unwrap_err();
+        assert!(err.to_string().contains("unsupported state version"));
+    }
+
+    #[cfg(feature = "zeroize")]
+    #[test]
+    fn test_zeroize_on_drop() {
+        use core::mem::ManuallyDrop;
+
+        let mut hasher = ManuallyDrop::new(Sha256::new());
+        hasher.update(b"secret key material");
+        let ptr: *const Sha256 = &*hasher;
+
+        // SAFETY: `ManuallyDrop` keeps the storage alive after the destructor has run, so the
+        // fields can still be read through `ptr`.
+        unsafe {
+            core::ptr::drop_in_place(&mut *hasher as *mut Sha256);
+            assert_eq!((*ptr).state, [0; 8]);
+            assert_eq!((*ptr).buffer, [0; BLOCK_SIZE]);
+            assert_eq!((*ptr).buffer_len, 0);
+            assert_eq!((*ptr).total_len, 0);
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_io_write() {
+        use std::io::Write;
+
+        let mut hasher = Sha256::new();
+        write!(hasher, "Hello, {}!", 42).unwrap();
+        hasher.flush().unwrap();
+        assert_eq!(hasher.finalize(), sha256(b"Hello, 42!"));
+    }
+
+    #[test]
+    fn test_sha256_hasher() {
+        use core::hash::Hasher;
+
+        let mut hasher = Sha256Hasher::default();
+        hasher.write(b"Hello, ");
+        hasher.write(b"world!");
+        let digest = sha256(b"Hello, world!");
+        assert_eq!(hasher.finish(), u64::from_be_bytes(digest[..8].try_into().unwrap()));
+
+        // Finishing does not consume the hasher
+        assert_eq!(hasher.finish(), hasher.finish());
+        hasher.write(b"!");
+        assert_ne!(hasher.finish(), u64::from_be_bytes(digest[..8].try_into().unwrap()));
+    }
+
+    #[test]
+    fn test_sha256_hasher_hash_map() {
+        use std::{collections::HashMap, hash::BuildHasherDefault};
+
+        #[derive(Hash, PartialEq, Eq)]
+        struct Key {
+            name:    String,
+            version: u32,
+        }
+
+        let mut map: HashMap<Key, usize, BuildHasherDefault<Sha256Hasher>> = HashMap::default();
+        for i in 0..100 {
+            map.insert(Key { name: format!("key {}", i), version: i }, i as usize);
+        }
+        for i in 0..100 {
+            assert_eq!(
+                map.get(&Key { name: format!("key {}", i), version: i }),
+                Some(&(i as usize))
+            );
+        }
+        assert_eq!(map.get(&Key { name: "key 0".to_string(), version: 1 }), None);
+    }
+
+    #[test]
+    fn test_streaming() {
+        let message: Vec<u8> = (0..1000).map(|i| i as u8).collect();
+
+        // Feed the message in chunks that do and do not align with block boundaries
+        for chunk_size in [1, 3, 55, 63, 64, 65, 128, 1000] {
+            let mut hasher = Sha256::new();
+            for chunk in message.chunks(chunk_size) {
+                hasher.update(chunk);
+            }
+            assert_eq!(hasher.finalize(), sha256(&message), "Chunk size {} failed", chunk_size);
+        }
+
+        // Padding boundaries
+        for len in [0, 55, 56, 63, 64, 119, 120] {
+            let mut hasher = Sha256::new();
+            hasher.update(&message[..len]);
+            assert_eq!(hasher.finalize(), sha256(&message[..len]), "Length {} failed", len);
+        }
+    }
+
+    #[test]
+    fn test_finalize_verify() {
+        let message = b"The quick brown fox jumps over the lazy dog";
+        let expected = sha256(message);
+        let mut off_by_one_bit = expected;
+        off_by_one_bit[31] ^= 1;
+
+        for (digest, valid) in [(expected, true), (off_by_one_bit, false)] {
+            let mut hasher = Sha256::new();
+            for chunk in message.chunks(3) {
+                hasher.update(chunk);
+            }
+            assert_eq!(bool::from(hasher.finalize_verify(&digest)), valid);
+        }
+    }
+
+    #[test]
+    fn test_bytes_processed() {
+        let data: Vec<u8> = (0..1000).map(|i| i as u8).collect();
+        let mut hasher = Sha256::new();
+        assert_eq!(hasher.bytes_processed(), 0);
+        for chunk in data.chunks(37) {
+            hasher.update(chunk);
+        }
+        assert_eq!(hasher.bytes_processed(), 1000);
+
+        #[cfg(feature = "std")]
+        {
+            use std::io::Write;
+            hasher.write_all(b"abc").unwrap();
+            assert_eq!(hasher.bytes_processed(), 1003);
+        }
+        hasher.reset();
+        assert_eq!(hasher.bytes_processed(), 0);
+    }
+
+    #[test]
+    fn test_finalize_words() {
+        let mut hasher = Sha256::new();
+        hasher.update(b"abc");
+        let words = hasher.clone().finalize_words();
+
+        let mut bytes = [0u8; 32];
+        for (chunk, word) in bytes.chunks_exact_mut(4).zip(words) {
+            chunk.copy_from_slice(&word.to_be_bytes());
+        }
+        assert_eq!(bytes, sha256(b"abc"));
+        assert_eq!(hasher.finalize(), bytes);
+        assert_eq!(words, [
+            0xba7816bf, 0x8f01cfea, 0x414140de, 0x5dae2223, 0xb00361a3, 0x96177a9c, 0xb410ff61,
+            0xf20015ad,
+        ]);
+    }
+
+    #[test]
+    fn test_update_iter() {
+        let bytes: Vec<u8> = (0u8..=255).cycle().take(200).collect();
+        let mut hasher = Sha256::new();
+        hasher.update_iter((0u8..=255).cycle().take(200));
+        assert_eq!(hasher.finalize(), sha256(&bytes));
+
+        // Mixed with slices, around a partially filled buffer
+        let mut hasher = Sha256::new();
+        hasher.update(&bytes[..10]);
+        hasher.update_iter(bytes[10..150].iter().copied());
+        hasher.update_iter(core::iter::empty());
+        hasher.update(&bytes[150..]);
+        assert_eq!(hasher.finalize(), sha256(&bytes));
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn test_trace_progress() {
+        use std::sync::{Arc, Mutex};
+
+        use tracing::{
+            field::{Field, Visit},
+            span, Event, Metadata, Subscriber,
+        };
+
+        /// Records the `bytes` field of every event.
+        #[derive(Default)]
+        struct Progress(Arc<Mutex<Vec<u64>>>);
+
+        struct BytesField(Option<u64>);
+
+        impl Visit for BytesField {
+            fn record_u64(&mut self, field: &Field, value: u64) {
+                if field.name() == "bytes" {
+                    self.0 = Some(value);
+                }
+            }
+
+            fn record_debug(&mut self, _: &Field, _: &dyn fmt::Debug) {}
+        }
+
+        impl Subscriber for Progress {
+            fn enabled(&self, _: &Metadata<'_>) -> bool { true }
+
+            fn new_span(&self, _: &span::Attributes<'_>) -> span::Id { span::Id::from_u64(1) }
+
+            fn record(&self, _: &span::Id, _: &span::Record<'_>) {}
+
+            fn record_follows_from(&self, _: &span::Id, _: &span::Id) {}
+
+            fn event(&self, event: &Event<'_>) {
+                let mut bytes = BytesField(None);
+                event.record(&mut bytes);
+                self.0.lock().unwrap().extend(bytes.0);
+            }
+
+            fn enter(&self, _: &span::Id) {}
+
+            fn exit(&self, _: &span::Id) {}
+        }
+
+        const INTERVAL: usize = TRACE_INTERVAL_BLOCKS as usize * BLOCK_SIZE;
+        let progress = Progress::default();
+        let events = Arc::clone(&progress.0);
+        tracing::subscriber::with_default(progress, || {
+            // Two and a half intervals in 1000-byte pieces
+            let mut hasher = Sha256::new();
+            for chunk in vec![0u8; 2 * INTERVAL + INTERVAL / 2].chunks(1000) {
+                hasher.update(chunk);
+            }
+            let _ = hasher.finalize();
+
+            let block = [0u8; BLOCK_SIZE];
+            let _ = compute_hash(IHV, &[&block[..]; TRACE_INTERVAL_BLOCKS as usize + 1]);
+        });
+
+        // One event per completed interval, with the bytes absorbed by the piece that completed it
+        assert_eq!(*events.lock().unwrap(), [66_000, 132_000, INTERVAL as u64]);
+    }
+
+    #[test]
+    fn test_update_integers() {
+        let mut hasher = Sha256::new();
+        hasher.update_u32_be(0x01020304);
+        hasher.update_u64_be(0x05060708090a0b0c);
+        assert_eq!(hasher.finalize(), sha256(&(1..=12).collect::<Vec<u8>>()));
+    }
+
+    #[test]
+    fn test_update_length_prefixed() {
+        let mut hasher = Sha256::new();
+        hasher.update_length_prefixed(b"ab");
+        assert_eq!(hasher.bytes_processed(), 6);
+        assert_eq!(hasher.finalize(), sha256(b"\0\0\0\x02ab"));
+
+        let split = |a: &[u8], b: &[u8]| {
+            let mut hasher = Sha256::new();
+            hasher.update_length_prefixed(a);
+            hasher.update_length_prefixed(b);
+            hasher.finalize()
+        };
+        let mut plain = Sha256::new();
+        plain.update(b"a");
+        plain.update(b"b");
+        assert_ne!(split(b"a", b"b"), plain.finalize());
+        assert_ne!(split(b"ab", b"c"), split(b"a", b"bc"));
+        assert_ne!(split(b"", b"ab"), split(b"ab", b""));
+    }
+
+    #[test]
+    fn test_chain() {
+        assert_eq!(Sha256::new().chain(b"ab").chain(b"c").finalize(), sha256(b"abc"));
+        assert_eq!(Sha256::from(&b"ab"[..]).chain(b"c").finalize(), sha256(b"abc"));
+        assert_eq!(Sha256::from(&b""[..]).finalize(), sha256(b""));
+
+        let message: Vec<u8> = (0..200).map(|i| i as u8).collect();
+        let hasher = Sha256::from(&message[..63]).chain(&message[63..130]).chain(&message[130..]);
+        assert_eq!(hasher.bytes_processed(), 200);
+        assert_eq!(hasher.finalize(), sha256(&message));
+    }
+
+    #[test]
+    fn test_update_blocks() {
+        let data: Vec<u8> = (0..300).map(|i| i as u8).collect();
+        let blocks: Vec<&[u8; BLOCK_SIZE]> =
+            data.chunks_exact(BLOCK_SIZE).map(|block| block.try_into().unwrap()).collect();
+
+        let mut hasher = Sha256::new();
+        hasher.update_blocks(&blocks[..2]);
+        hasher.update(&data[128..192]);
+        hasher.update_blocks(&blocks[3..]);
+        hasher.update_blocks(&[]);
+        hasher.update(&data[256..]);
+        assert_eq!(hasher.bytes_processed(), 300);
+        assert_eq!(hasher.finalize(), sha256(&data));
+    }
+
+    #[test]
+    #[should_panic(expected = "partial block buffered")]
+    fn test_update_blocks_unaligned() {
+        let mut hasher = Sha256::new();
+        hasher.update(b"abc");
+        hasher.update_blocks(&[&[0; BLOCK_SIZE]]);
+    }
+
+    #[test]
+    fn test_try_update_length_limit() {
+        let mut hasher = Sha256::new();
+        hasher.update(b"abc");
+
+        // Pretend almost the whole length range has been absorbed
+        hasher.total_len = MAX_INPUT_LEN - 3;
+        assert_eq!(hasher.try_update(b"de"), Ok(()));
+        assert_eq!(hasher.try_update(b"fg"), Err(ShaError::InputTooLong(MAX_INPUT_LEN + 1)));
+        assert_eq!(hasher.try_update(b"f"), Ok(()));
+        assert_eq!(hasher.total_len, MAX_INPUT_LEN);
+        assert_eq!(hasher.try_update(b""), Ok(()));
+        assert_eq!(hasher.buffer[..hasher.buffer_len], *b"abcdef");
+
+        // Overflowing the counter itself is reported too
+        hasher.total_len = u64::MAX;
+        assert_eq!(hasher.try_update(b"g"), Err(ShaError::InputTooLong(u64::MAX)));
+        assert_eq!(
+            ShaError::InputTooLong(MAX_INPUT_LEN + 1).to_string(),
+            "input length 2305843009213693952 exceeds maximum 2305843009213693951 bytes"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "exceeds maximum")]
+    fn test_update_length_limit() {
+        let mut hasher = Sha256::new();
+        hasher.total_len = MAX_INPUT_LEN;
+        hasher.update(b"a");
+    }
+
+    #[test]
+    fn test_finalize_reset() {
+        let long = b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq";
+        let mut hasher = Sha256::new();
+        hasher.update(b"abc");
+        assert_eq!(hasher.finalize_reset(), sha256(b"abc"));
+        hasher.update(long);
+        assert_eq!(hasher.finalize_reset(), sha256(long));
+
+        // Nothing is left over, not even a partial block or the length
+        assert_eq!(hasher.finalize_reset(), sha256(b""));
+        hasher.update(&[0x61; 70]);
+        hasher.reset();
+        assert_eq!((hasher.state, hasher.buffer_len, hasher.total_len), (IHV, 0, 0));
+        assert_eq!(hasher.finalize(), sha256(b""));
+    }
+
+    #[test]
+    fn test_checkpoint() {
+        let mut hasher = Sha256::new();
+        assert_eq!(hasher.checkpoint(), sha256(b""));
+        hasher.update(b"abc");
+        assert_eq!(hasher.checkpoint(), sha256(b"abc"));
+        assert_eq!(hasher.checkpoint(), sha256(b"abc"));
+
+        // Checkpoints on and off block boundaries don't disturb the running hash
+        let message: Vec<u8> = (0..300).map(|i| i as u8).collect();
+        hasher.update(&message[..61]);
+        assert_eq!(hasher.checkpoint(), sha256(&[&b"abc"[..], &message[..61]].concat()));
+        hasher.update(&message[61..]);
+        assert_eq!(hasher.bytes_processed(), 303);
+        assert_eq!(hasher.finalize(), sha256(&[&b"abc"[..], &message].concat()));
+    }
+
+    #[test]
+    fn test_finalize_into() {
+        for message in [&b""[..], b"abc", &[0x61; 70], &[0x5a; 1000]] {
+            let mut out = [0u8; 32];
+            sha256_into(message, &mut out);
+            assert_eq!(out, sha256(message));
+
+            let mut hasher = Sha256::new();
+            hasher.update(message);
+            let mut out = [0u8; 32];
+            hasher.finalize_into(&mut out);
+            assert_eq!(out, sha256(message));
         }
+
+        // A slice of a larger buffer, only the selected bytes are written
+        let mut buffer = [0xffu8; 48];
+        sha256_into(b"abc", (&mut buffer[8..40]).try_into().unwrap());
+        assert_eq!(buffer[8..40], sha256(b"abc"));
+        assert_eq!(buffer[..8], [0xff; 8]);
+        assert_eq!(buffer[40..], [0xff; 8]);
+
+        let mut hasher = Sha256::new();
+        hasher.update(b"abc");
+        hasher.finalize_into((&mut buffer[16..48]).try_into().unwrap());
+        assert_eq!(buffer[16..48], sha256(b"abc"));
+
+        // Slices of the wrong length don't convert
+        assert!(<&mut [u8; 32]>::try_from(&mut buffer[..31]).is_err());
+    }
+
+    #[test]
+    fn test_clone() {
+        let mut prefix = Sha256::new();
+        prefix.update(b"tree node: ");
+
+        let mut left = prefix.clone();
+        let mut right = prefix;
+        left.update(b"left child");
+        right.update(b"right child, long enough to spill over into the next block");
+        assert_eq!(left.finalize(), sha256(b"tree node: left child"));
+        assert_eq!(
+            right.finalize(),
+            sha256(b"tree node: right child, long enough to spill over into the next block")
+        );
     }
 }
@@ -0,0 +1,78 @@
+//! SHA-256 compression using the ARMv8 Cryptography Extensions.
+//!
+//! `sha256h`/`sha256h2` perform four rounds at a time on the `ABCD` and `EFGH` halves of the
+//! state, while `sha256su0`/`sha256su1` compute the next four words of the message schedule.
+//!
+//! # References
+//!
+//! - [Arm A64 Instruction Set Architecture](https://developer.arm.com/documentation/ddi0602/latest/SIMD-FP-Instructions)
+
+use core::arch::aarch64::*;
+
+use super::{BLOCK_SIZE, WORDS_K_GROUPED};
+
+/// Check whether the CPU supports the SHA-256 instructions.
+///
+/// Without `std` this can only be decided at compile time, e.g. with `-C target-cpu=native`.
+#[inline]
+pub(super) fn sha2_available() -> bool {
+    #[cfg(feature = "std")]
+    {
+        std::arch::is_aarch64_feature_detected!("sha2")
+    }
+    #[cfg(not(feature = "std"))]
+    {
+        cfg!(target_feature = "sha2")
+    }
+}
+
+/// Process every complete 512-bit block of `data` with the Cryptography Extensions.
+///
+/// See: FIPS 180-4, 6.2.2
+///
+/// # Parameters
+///
+/// - `hash_value`: Intermediate hash value, updated in place.
+/// - `data`: Message blocks `M^(1)..M^(N)`.
+///
+/// # Safety
+///
+/// The CPU must support the features checked by [`sha2_available`].
+#[target_feature(enable = "sha2")]
+pub(super) unsafe fn compress_blocks_neon(hash_value: &mut [u32; 8], data: &[u8]) {
+    let mut abcd = vld1q_u32(hash_value.as_ptr());
+    let mut efgh = vld1q_u32(hash_value.as_ptr().add(4));
+
+    for block in data.chunks_exact(BLOCK_SIZE) {
+        let (abcd_prev, efgh_prev) = (abcd, efgh);
+
+        // Message schedule as a ring of the last sixteen words, four per register
+        let mut w = [vdupq_n_u32(0); 4];
+        for (g, words) in w.iter_mut().enumerate() {
+            // Byte loads have no alignment requirement, blocks come straight from caller slices
+            let bytes = vrev32q_u8(vld1q_u8(block.as_ptr().add(16 * g)));
+            *words = vreinterpretq_u32_u8(bytes);
+        }
+
+        for g in 0..16 {
+            // Four rounds, `sha256h2` needs `ABCD` from before `sha256h`
+            let wk = vaddq_u32(w[g % 4], vld1q_u32(WORDS_K_GROUPED[g].as_ptr()));
+            let abcd_round = abcd;
+            abcd = vsha256hq_u32(abcd, efgh, wk);
+            efgh = vsha256h2q_u32(efgh, abcd_round, wk);
+
+            // W_t = sigma1(W_t-2) + W_t-7 + sigma0(W_t-15) + W_t-16, for the group after next
+            if g < 12 {
+                let partial = vsha256su0q_u32(w[g % 4], w[(g + 1) % 4]);
+                w[g % 4] = vsha256su1q_u32(partial, w[(g + 2) % 4], w[(g + 3) % 4]);
+            }
+        }
+
+        // Compute intermediate hash values
+        abcd = vaddq_u32(abcd, abcd_prev);
+        efgh = vaddq_u32(efgh, efgh_prev);
+    }
+
+    vst1q_u32(hash_value.as_mut_ptr(), abcd);
+    vst1q_u32(hash_value.as_mut_ptr().add(4), efgh);
+}
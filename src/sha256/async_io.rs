@@ -0,0 +1,167 @@
+//! Hashing of [`futures_io`] streams.
+
+use core::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+use std::io;
+
+use futures_io::AsyncWrite;
+
+use super::Sha256;
+
+/// Async writer adapter that hashes everything written through it.
+///
+/// The async counterpart of [`HashingWriter`](super::HashingWriter) for the `futures` I/O traits,
+/// which tokio streams implement through `tokio-util`'s compat layer. Only the bytes the inner
+/// writer accepts are hashed, so pending and short writes are hashed correctly.
+///
+/// # Examples
+///
+/// ```
+/// use futures::{executor::block_on, io::AsyncWriteExt};
+/// use shs_rs::sha256::{sha256, AsyncHashingWriter};
+///
+/// block_on(async {
+///     let mut writer = AsyncHashingWriter::new(Vec::new());
+///     writer.write_all(b"Hello, world!").await?;
+///     writer.close().await?;
+///     assert_eq!(writer.digest(), sha256(b"Hello, world!"));
+///     Ok::<(), std::io::Error>(())
+/// })?;
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[derive(Clone)]
+pub struct AsyncHashingWriter<W> {
+    inner:  W,
+    hasher: Sha256,
+}
+
+impl<W: AsyncWrite + Unpin> AsyncHashingWriter<W> {
+    /// Wrap a writer, starting with an empty message.
+    ///
+    /// # Parameters
+    ///
+    /// - `inner`: Writer to forward writes to.
+    pub fn new(inner: W) -> Self { Self { inner, hasher: Sha256::new() } }
+
+    /// Digest of the bytes written so far.
+    ///
+    /// # Returns
+    ///
+    /// 256-bit digest of everything the inner writer accepted since it was wrapped.
+    #[must_use]
+    pub fn digest(&self) -> [u8; 32] { self.hasher.checkpoint() }
+
+    /// Reference to the inner writer.
+    pub fn get_ref(&self) -> &W { &self.inner }
+
+    /// Unwrap the inner writer, discarding the hash.
+    pub fn into_inner(self) -> W { self.inner }
+}
+
+impl<W: AsyncWrite + Unpin> AsyncWrite for AsyncHashingWriter<W> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        let n = match Pin::new(&mut this.inner).poll_write(cx, buf) {
+            Poll::Ready(Ok(n)) => n,
+            other => return other,
+        };
+        this.hasher.update(&buf[..n]);
+        Poll::Ready(Ok(n))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().inner).poll_flush(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().inner).poll_close(cx)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use futures::{
+        executor::block_on,
+        io::{copy, AsyncWriteExt, Cursor},
+    };
+
+    use super::*;
+    use crate::sha256::sha256;
+
+    /// Accepts at most `limit` bytes per write, and is pending before every other write.
+    struct SlowWriter {
+        written: Vec<u8>,
+        limit:   usize,
+        pending: bool,
+        closed:  bool,
+    }
+
+    impl AsyncWrite for SlowWriter {
+        fn poll_write(
+            mut self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &[u8],
+        ) -> Poll<io::Result<usize>> {
+            self.pending = !self.pending;
+            if self.pending {
+                cx.waker().wake_by_ref();
+                return Poll::Pending;
+            }
+            let n = buf.len().min(self.limit);
+            self.written.extend_from_slice(&buf[..n]);
+            Poll::Ready(Ok(n))
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn poll_close(mut self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+            self.closed = true;
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    #[test]
+    fn test_async_hashing_writer() {
+        let data: Vec<u8> = (0..10_000).map(|i| i as u8).collect();
+        block_on(async {
+            let mut writer = AsyncHashingWriter::new(Cursor::new(Vec::new()));
+            for chunk in data.chunks(777) {
+                writer.write_all(chunk).await.unwrap();
+            }
+            writer.flush().await.unwrap();
+            assert_eq!(writer.get_ref().get_ref(), &data);
+            assert_eq!(writer.digest(), sha256(&data));
+
+            // Checksum a stream while copying it
+            let mut writer = AsyncHashingWriter::new(Cursor::new(Vec::new()));
+            copy(Cursor::new(&data), &mut writer).await.unwrap();
+            assert_eq!(writer.digest(), sha256(&data));
+            assert_eq!(writer.into_inner().into_inner(), data);
+        });
+    }
+
+    #[test]
+    fn test_async_hashing_writer_partial() {
+        let writer = SlowWriter { written: Vec::new(), limit: 3, pending: false, closed: false };
+        let mut writer = AsyncHashingWriter::new(writer);
+        block_on(async {
+            // Pending writes hash nothing, short writes only what was accepted
+            assert_eq!(writer.write(b"Hello, world!").await.unwrap(), 3);
+            assert_eq!(writer.digest(), sha256(b"Hel"));
+            writer.write_all(b"lo, world!").await.unwrap();
+            writer.close().await.unwrap();
+        });
+        assert_eq!(writer.digest(), sha256(b"Hello, world!"));
+        let inner = writer.into_inner();
+        assert_eq!(inner.written, b"Hello, world!");
+        assert!(inner.closed);
+    }
+}
@@ -0,0 +1,313 @@
+//! Selection of the SHA-256 compression backend.
+//!
+//! By default the fastest backend supported by the CPU is detected at runtime. For benchmarking
+//! and debugging, a specific backend can be used for a single computation with
+//! [`compute_hash_with_backend`], or for every computation with [`set_backend_override`].
+//! [`active_backend`] tells which one is in use, e.g. for logging.
+
+use core::{
+    fmt,
+    sync::atomic::{AtomicU8, Ordering},
+};
+
+#[cfg(target_arch = "aarch64")] use super::aarch64;
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+use super::x86;
+use super::{compress_blocks_portable, state_to_bytes, BLOCK_SIZE};
+
+/// Implementation of the SHA-256 compression function.
+///
+/// All backends produce identical results, they only differ in speed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Backend {
+    /// Portable Rust, available everywhere.
+    Scalar,
+    /// SSE2 message schedule, available on every x86_64 processor.
+    Sse2,
+    /// AVX2 message schedule of two blocks at once.
+    Avx2,
+    /// x86 SHA extensions.
+    ShaNi,
+    /// aarch64 SHA2 extension.
+    Neon,
+}
+
+impl Backend {
+    /// All backends, whether available or not.
+    pub const ALL: [Backend; 5] =
+        [Backend::Scalar, Backend::Sse2, Backend::Avx2, Backend::ShaNi, Backend::Neon];
+
+    /// Check whether this backend can run on the current CPU.
+    ///
+    /// Without `std` this can only be decided at compile time, e.g. with `-C target-cpu=native`.
+    pub fn is_available(self) -> bool {
+        match self {
+            Backend::Scalar => true,
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            Backend::Sse2 => x86::sse2_available(),
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            Backend::Avx2 => x86::avx2_available(),
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            Backend::ShaNi => x86::sha_ni_available(),
+            #[cfg(target_arch = "aarch64")]
+            Backend::Neon => aarch64::sha2_available(),
+            #[allow(unreachable_patterns)]
+            _ => false,
+        }
+    }
+
+    /// Fastest backend available on the current CPU.
+    pub fn detect() -> Self {
+        [Backend::ShaNi, Backend::Neon, Backend::Avx2, Backend::Sse2]
+            .into_iter()
+            .find(|backend| backend.is_available())
+            .unwrap_or(Backend::Scalar)
+    }
+
+    /// Process every complete 512-bit block of `data` with this backend.
+    ///
+    /// # Safety
+    ///
+    /// The backend must be available, see [`Backend::is_available`].
+    #[inline]
+    pub(super) unsafe fn compress_blocks(self, hash_value: &mut [u32; 8], data: &[u8]) {
+        match self {
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            Backend::Sse2 => x86::compress_blocks_sse2(hash_value, data),
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            Backend::Avx2 => x86::compress_blocks_avx2(hash_value, data),
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            Backend::ShaNi => x86::compress_blocks_sha_ni(hash_value, data),
+            #[cfg(target_arch = "aarch64")]
+            Backend::Neon => aarch64::compress_blocks_neon(hash_value, data),
+            _ => compress_blocks_portable(hash_value, data),
+        }
+    }
+
+    /// Encoding of the backend in [`OVERRIDE`], zero stands for none.
+    fn to_u8(self) -> u8 {
+        match self {
+            Backend::Scalar => 1,
+            Backend::Sse2 => 2,
+            Backend::Avx2 => 3,
+            Backend::ShaNi => 4,
+            Backend::Neon => 5,
+        }
+    }
+
+    /// Inverse of [`Backend::to_u8`].
+    fn from_u8(value: u8) -> Option<Self> {
+        Self::ALL.get((value as usize).checked_sub(1)?).copied()
+    }
+}
+
+impl fmt::Display for Backend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Backend::Scalar => "scalar",
+            Backend::Sse2 => "SSE2",
+            Backend::Avx2 => "AVX2",
+            Backend::ShaNi => "SHA-NI",
+            Backend::Neon => "NEON",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Error returned when selecting a backend the current CPU does not support.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnsupportedBackend(pub Backend);
+
+impl fmt::Display for UnsupportedBackend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} backend is not supported by this CPU", self.0)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for UnsupportedBackend {}
+
+#[cfg(not(feature = "std"))]
+impl core::error::Error for UnsupportedBackend {}
+
+/// Backend forced by [`set_backend_override`], encoded with [`Backend::to_u8`].
+static OVERRIDE: AtomicU8 = AtomicU8::new(0);
+
+/// Force every SHA-256 computation in the process to use `backend`.
+///
+/// # Parameters
+///
+/// - `backend`: Backend to use, or `None` to go back to runtime detection.
+///
+/// # Returns
+///
+/// An error, leaving the current selection unchanged, if `backend` can't run on this CPU.
+///
+/// # Examples
+///
+/// ```
+/// use shs_rs::sha256::{set_backend_override, sha256, Backend};
+///
+/// set_backend_override(Some(Backend::Scalar))?;
+/// let digest = sha256(b"abc");
+/// set_backend_override(None)?;
+/// assert_eq!(digest, sha256(b"abc"));
+/// # Ok::<(), shs_rs::sha256::UnsupportedBackend>(())
+/// ```
+pub fn set_backend_override(backend: Option<Backend>) -> Result<(), UnsupportedBackend> {
+    let value = match backend {
+        Some(backend) if !backend.is_available() => return Err(UnsupportedBackend(backend)),
+        Some(backend) => backend.to_u8(),
+        None => 0,
+    };
+    OVERRIDE.store(value, Ordering::Relaxed);
+    Ok(())
+}
+
+/// Backend used by computations that don't name one, like [`sha256`](super::sha256).
+///
+/// # Returns
+///
+/// The backend set with [`set_backend_override`], otherwise [`Backend::detect`].
+///
+/// # Examples
+///
+/// ```
+/// use shs_rs::sha256::active_backend;
+///
+/// println!("SHA-256 backend: {}", active_backend());
+/// ```
+#[inline]
+pub fn active_backend() -> Backend {
+    Backend::from_u8(OVERRIDE.load(Ordering::Relaxed)).unwrap_or_else(Backend::detect)
+}
+
+/// SHA-256 Hash Computation over contiguous blocks with a specific backend.
+///
+/// Same as [`compute_hash_bytes`](super::compute_hash_bytes), but without runtime detection.
+///
+/// See: FIPS 180-4, 6.2.2
+///
+/// # Parameters
+///
+/// - `backend`: Backend to compute with.
+/// - `initial_state`: Hash value to start from, usually [`IHV`](super::IHV).
+/// - `data`: A message to compute digest over, already padded to a multiple of 512 bits.
+///
+/// # Returns
+///
+/// A 256-bit digest of `data`, or an error if `backend` can't run on this CPU.
+///
+/// # Panics
+///
+/// If `data.len()` is not a multiple of [`BLOCK_SIZE`].
+///
+/// # Examples
+///
+/// ```
+/// use shs_rs::sha256::{
+///     compute_hash_bytes, compute_hash_with_backend, padding_into, Backend, BLOCK_SIZE, IHV,
+/// };
+///
+/// let mut buffer = [0u8; 128];
+/// let blocks = padding_into(b"abc", &mut buffer);
+/// let padded = &buffer[..blocks * BLOCK_SIZE];
+/// let digest = compute_hash_with_backend(Backend::Scalar, IHV, padded)?;
+/// assert_eq!(digest, compute_hash_bytes(IHV, padded));
+/// # Ok::<(), shs_rs::sha256::UnsupportedBackend>(())
+/// ```
+pub fn compute_hash_with_backend(
+    backend: Backend,
+    initial_state: [u32; 8],
+    data: &[u8],
+) -> Result<[u8; 32], UnsupportedBackend> {
+    assert_eq!(data.len() % BLOCK_SIZE, 0, "data must consist of complete 512-bit blocks");
+    if !backend.is_available() {
+        return Err(UnsupportedBackend(backend));
+    }
+
+    let mut hash_value = initial_state;
+    // SAFETY: the backend was just checked to be available
+    unsafe { backend.compress_blocks(&mut hash_value, data) };
+    Ok(state_to_bytes(&hash_value))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::sha256::IHV;
+    #[cfg(feature = "alloc")]
+    use crate::sha256::{compute_hash_bytes, padding, sha256};
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_compute_hash_with_backend() {
+        use rand::Rng;
+
+        let mut rng = crate::test_rng::test_rng();
+        for len in [0, 1, 55, 56, 64, 128, 1000] {
+            let message: Vec<u8> = (0..len).map(|_| rng.gen()).collect();
+            let padded = padding(&message);
+            for backend in Backend::ALL {
+                match compute_hash_with_backend(backend, IHV, &padded) {
+                    Ok(digest) => assert_eq!(digest, sha256(&message), "{} failed", backend),
+                    Err(err) => {
+                        assert!(!backend.is_available());
+                        assert_eq!(err, UnsupportedBackend(backend));
+                    },
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_detect() {
+        assert!(Backend::detect().is_available());
+        assert!(active_backend().is_available());
+        assert!(Backend::Scalar.is_available());
+
+        // A CPU is either x86 or ARM
+        assert!(!(Backend::Neon.is_available() && Backend::Sse2.is_available()));
+        #[cfg(not(target_arch = "aarch64"))]
+        assert_eq!(
+            compute_hash_with_backend(Backend::Neon, IHV, &[]),
+            Err(UnsupportedBackend(Backend::Neon))
+        );
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_backend_override() {
+        let padded = padding(b"abc");
+        let expected = compute_hash_bytes(IHV, &padded);
+
+        // Other tests may hash in the meantime, which every backend does correctly
+        set_backend_override(Some(Backend::Scalar)).unwrap();
+        assert_eq!(active_backend(), Backend::Scalar);
+        assert_eq!(compute_hash_bytes(IHV, &padded), expected);
+
+        for backend in Backend::ALL.into_iter().filter(|backend| !backend.is_available()) {
+            assert_eq!(set_backend_override(Some(backend)), Err(UnsupportedBackend(backend)));
+            assert_eq!(active_backend(), Backend::Scalar);
+        }
+
+        // The override applies to one-shot hashing too
+        assert_eq!(sha256(b"abc"), expected);
+        assert_eq!(active_backend(), Backend::Scalar);
+
+        set_backend_override(None).unwrap();
+        assert_eq!(active_backend(), Backend::detect());
+        assert_eq!(
+            UnsupportedBackend(Backend::Neon).to_string(),
+            "NEON backend is not supported by this CPU"
+        );
+    }
+
+    #[test]
+    fn test_backend_encoding() {
+        for backend in Backend::ALL {
+            assert_eq!(Backend::from_u8(backend.to_u8()), Some(backend));
+        }
+        assert_eq!(Backend::from_u8(0), None);
+    }
+}
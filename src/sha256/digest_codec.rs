@@ -0,0 +1,165 @@
+//! Canonical serde encoding of [`Digest`].
+//!
+//! A digest is serialized as a single 32-byte byte string, never as a sequence of numbers, so
+//! formats with a native byte type store it compactly: 34 bytes in CBOR (a two-byte header, then
+//! the digest) and 40 in bincode (a 64-bit length, then the digest). Deserialization accepts
+//! byte strings and, for formats like JSON that have none, sequences of exactly 32 bytes.
+//!
+//! [`Digest`] uses this encoding for its `Serialize`/`Deserialize` implementations. The functions
+//! here also work with `#[serde(with = "shs_rs::sha256::digest_codec")]` on a `[u8; 32]` field.
+//!
+//! # Examples
+//!
+//! ```
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Manifest {
+//!     #[serde(with = "shs_rs::sha256::digest_codec")]
+//!     checksum: [u8; 32],
+//! }
+//! ```
+
+use core::fmt;
+
+use serde::{de, Deserializer, Serializer};
+
+use super::Digest;
+
+/// Serialize a digest as a 32-byte byte string.
+///
+/// # Parameters
+///
+/// - `digest`: Digest to serialize, a [`Digest`] or its `[u8; 32]` bytes.
+/// - `serializer`: Target format.
+pub fn serialize<T: AsRef<[u8]>, S: Serializer>(
+    digest: &T,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_bytes(digest.as_ref())
+}
+
+/// Deserialize a digest from a 32-byte byte string or sequence.
+///
+/// # Parameters
+///
+/// - `deserializer`: Source format.
+///
+/// # Returns
+///
+/// The digest, or an error if the input is not exactly 32 bytes.
+pub fn deserialize<'de, T: From<[u8; 32]>, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<T, D::Error> {
+    deserializer.deserialize_bytes(DigestVisitor).map(T::from)
+}
+
+struct DigestVisitor;
+
+impl<'de> de::Visitor<'de> for DigestVisitor {
+    type Value = [u8; 32];
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result { write!(f, "32 bytes of digest") }
+
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+        v.try_into().map_err(|_| E::invalid_length(v.len(), &self))
+    }
+
+    fn visit_seq<A: de::SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut bytes = [0u8; 32];
+        for (i, byte) in bytes.iter_mut().enumerate() {
+            *byte = seq.next_element()?.ok_or_else(|| de::Error::invalid_length(i, &self))?;
+        }
+        if seq.next_element::<u8>()?.is_some() {
+            return Err(de::Error::invalid_length(33, &self));
+        }
+        Ok(bytes)
+    }
+}
+
+impl Digest {
+    /// Serialize the digest as a 32-byte byte string, see [`digest_codec`](self).
+    ///
+    /// # Parameters
+    ///
+    /// - `serializer`: Target format.
+    pub fn serialize_fixed<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize(self, serializer)
+    }
+
+    /// Deserialize a digest from a 32-byte byte string or sequence, see [`digest_codec`](self).
+    ///
+    /// # Parameters
+    ///
+    /// - `deserializer`: Source format.
+    pub fn deserialize_fixed<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize(deserializer)
+    }
+}
+
+impl serde::Serialize for Digest {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.serialize_fixed(serializer)
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for Digest {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Self::deserialize_fixed(deserializer)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::sha256::sha256_digest;
+
+    #[test]
+    fn test_digest_cbor() {
+        let digest = sha256_digest(b"abc");
+        let mut cbor = Vec::new();
+        ciborium::into_writer(&digest, &mut cbor).unwrap();
+
+        // Byte string (major type 2) with a one-byte length, then the digest itself
+        assert_eq!(cbor.len(), 2 + 32);
+        assert_eq!(cbor[..2], [0x58, 0x20]);
+        assert_eq!(cbor[2..], digest.0);
+        assert_eq!(ciborium::from_reader::<Digest, _>(&cbor[..]).unwrap(), digest);
+
+        // Too short for a digest
+        let mut short = Vec::new();
+        ciborium::into_writer(&ciborium::Value::Bytes(digest.0[..31].to_vec()), &mut short)
+            .unwrap();
+        assert!(ciborium::from_reader::<Digest, _>(&short[..]).is_err());
+    }
+
+    #[test]
+    fn test_digest_bincode_and_json() {
+        let digest = sha256_digest(b"abc");
+        let encoded = bincode::serialize(&digest).unwrap();
+        assert_eq!(encoded[..8], 32u64.to_le_bytes());
+        assert_eq!(encoded[8..], digest.0);
+        assert_eq!(bincode::deserialize::<Digest>(&encoded).unwrap(), digest);
+
+        // JSON has no byte strings, so the digest is a sequence there
+        let json = serde_json::to_string(&digest).unwrap();
+        assert_eq!(serde_json::from_str::<Digest>(&json).unwrap(), digest);
+        assert!(serde_json::from_str::<Digest>("[1, 2, 3]").is_err());
+        let too_long = format!("{}, 0]", &json[..json.len() - 1]);
+        assert!(serde_json::from_str::<Digest>(&too_long).is_err());
+    }
+
+    #[test]
+    fn test_digest_codec_with() {
+        #[derive(serde::Serialize, serde::Deserialize, PartialEq, Debug)]
+        struct Manifest {
+            #[serde(with = "crate::sha256::digest_codec")]
+            checksum: [u8; 32],
+        }
+
+        let manifest = Manifest { checksum: sha256_digest(b"abc").0 };
+        let encoded = bincode::serialize(&manifest).unwrap();
+        assert_eq!(encoded, bincode::serialize(&sha256_digest(b"abc")).unwrap());
+        assert_eq!(bincode::deserialize::<Manifest>(&encoded).unwrap(), manifest);
+    }
+}
@@ -0,0 +1,120 @@
+//! Hashing with a running time that depends on a public maximum length only.
+//!
+//! The length of a message leaks through the number of blocks [`sha256`](super::sha256)
+//! compresses. When the length itself is secret, e.g. a password or a padded record,
+//! [`sha256_fixed_time`] always compresses as many blocks as the longest allowed message needs.
+
+use subtle::{ConditionallySelectable, ConstantTimeEq, ConstantTimeLess};
+
+use super::{compress_block, state_to_bytes, BLOCK_SIZE, IHV};
+
+/// Compute SHA-256 digest of a message without revealing its length through timing.
+///
+/// Runs the portable compression function on a number of blocks that only depends on `max_len`.
+/// Every block is assembled with constant-time selects from every position of the padded
+/// maximum-length message, so the work is `O(max_len)` even for short messages.
+///
+/// # Construction
+///
+/// For a message of `len <= max_len` bytes, the padded message of FIPS 180-4, 5.1.1 ends in block
+/// `f = (len + 8) / 64`. The function runs `(max_len + 8) / 64 + 1` compressions, the block count
+/// of a message of `max_len` bytes, on blocks assembled byte by byte without branching on `len`:
+///
+/// - byte `p` of the input is `message[p]` for `p < len`, `0x80` for `p == len`, and zero after;
+/// - the last eight bytes of block `f` additionally hold the message length in bits.
+///
+/// Up to and including block `f` this is exactly the padded message. The compressions after it
+/// process dummy blocks of zeros, and their output is discarded: the intermediate hash value after
+/// block `f` is kept with a constant-time select, so the digest always equals `sha256(message)`.
+///
+/// The guarantee covers the instructions executed, not the memory accessed: bytes of `message`
+/// are only read below its length, so an attacker watching the cache lines of the input buffer
+/// can still learn the length at cache line granularity. `max_len` is public.
+///
+/// # Parameters
+///
+/// - `message`: Input message to hash, whose length is secret.
+/// - `max_len`: Public upper bound on the length of `message`.
+///
+/// # Returns
+///
+/// 256-bit digest of the `message`, equal to [`sha256`](super::sha256).
+///
+/// # Panics
+///
+/// If `message` is longer than `max_len`.
+///
+/// # Examples
+///
+/// ```
+/// use shs_rs::sha256::{sha256, sha256_fixed_time};
+///
+/// // Hashing a short and a long password takes the same time
+/// assert_eq!(sha256_fixed_time(b"hunter2", 128), sha256(b"hunter2"));
+/// assert_eq!(sha256_fixed_time(&[b'x'; 100], 128), sha256(&[b'x'; 100]));
+/// ```
+#[must_use]
+pub fn sha256_fixed_time(message: &[u8], max_len: usize) -> [u8; 32] {
+    assert!(message.len() <= max_len, "message is longer than max_len");
+
+    let len = message.len() as u64;
+    let final_block = (len + 8) / BLOCK_SIZE as u64;
+    let bit_len = (len * 8).to_be_bytes();
+    // Read from a one-byte stand-in when the message is empty, so every read below hits; chosen
+    // by index rather than by a branch on the length
+    let sources: [&[u8]; 2] = [message, &[0]];
+    let source = sources[usize::from(len.ct_eq(&0).unwrap_u8())];
+
+    let mut hash_value = IHV;
+    let mut result = IHV;
+    for i in 0..=(max_len as u64 + 8) / BLOCK_SIZE as u64 {
+        let is_final = i.ct_eq(&final_block);
+        let mut block = [0u8; BLOCK_SIZE];
+        for (j, byte) in block.iter_mut().enumerate() {
+            let position = i * BLOCK_SIZE as u64 + j as u64;
+            let in_message = position.ct_lt(&len);
+
+            // Read a message byte below the length and the first byte of `source` above it
+            let index = u64::conditional_select(&0, &position, in_message) as usize;
+            let data = source[index];
+            *byte = u8::conditional_select(&0, &data, in_message)
+                | u8::conditional_select(&0, &0x80, position.ct_eq(&len));
+            if let Some(&length_byte) = bit_len.get(j.wrapping_sub(BLOCK_SIZE - 8)) {
+                *byte |= u8::conditional_select(&0, &length_byte, is_final);
+            }
+        }
+
+        compress_block(&mut hash_value, &block);
+        for (word, candidate) in result.iter_mut().zip(hash_value) {
+            word.conditional_assign(&candidate, is_final);
+        }
+    }
+    state_to_bytes(&result)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::sha256::sha256;
+
+    #[test]
+    fn test_sha256_fixed_time() {
+        let data: Vec<u8> = (0..300).map(|i| i as u8).collect();
+        for max_len in [0, 1, 55, 56, 63, 64, 119, 120, 200] {
+            for len in 0..=max_len {
+                assert_eq!(
+                    sha256_fixed_time(&data[..len], max_len),
+                    sha256(&data[..len]),
+                    "len {} max_len {}",
+                    len,
+                    max_len
+                );
+            }
+        }
+        assert_eq!(sha256_fixed_time(&[0x80; 64], 1000), sha256(&[0x80; 64]));
+    }
+
+    #[test]
+    #[should_panic(expected = "longer than max_len")]
+    fn test_sha256_fixed_time_too_long() { let _ = sha256_fixed_time(b"abc", 2); }
+}
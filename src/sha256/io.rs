@@ -0,0 +1,588 @@
+//! Hashing of [`std::io`] streams.
+
+use std::{
+    fs::File,
+    io::{self, Read, Write},
+    path::Path,
+    time::{Duration, Instant},
+};
+
+use super::{Sha256, BLOCK_SIZE};
+
+/// Size of the buffer reads are made into.
+const READ_BUFFER_SIZE: usize = 8 * 1024;
+
+/// Compute SHA-256 digest of everything a reader yields.
+///
+/// The input is read in 8 KiB chunks, so it never has to be held in memory as a whole.
+///
+/// # Parameters
+///
+/// - `reader`: Source to read until end of file, e.g. a [`std::fs::File`].
+///
+/// # Returns
+///
+/// 256-bit digest of the read bytes, or the first I/O error other than
+/// [`io::ErrorKind::Interrupted`], which is retried.
+///
+/// # Examples
+///
+/// ```
+/// use std::io::Cursor;
+///
+/// use shs_rs::sha256::{sha256, sha256_reader};
+///
+/// let digest = sha256_reader(Cursor::new(b"Hello, world!"))?;
+/// assert_eq!(digest, sha256(b"Hello, world!"));
+/// # Ok::<(), std::io::Error>(())
+/// ```
+pub fn sha256_reader<R: Read>(reader: R) -> io::Result<[u8; 32]> {
+    hash_reader(reader, &mut [0u8; READ_BUFFER_SIZE])
+}
+
+/// Compute SHA-256 digest of everything a reader yields, reading in chunks of a chosen size.
+///
+/// Larger buffers mean fewer reads, which can pay off for slow storage like spinning disks.
+///
+/// # Parameters
+///
+/// - `reader`: Source to read until end of file.
+/// - `capacity`: Size of the read buffer in bytes, rounded up to a multiple of the 64-byte block
+///   size.
+///
+/// # Returns
+///
+/// 256-bit digest of the read bytes, or the first I/O error other than
+/// [`io::ErrorKind::Interrupted`], which is retried.
+///
+/// # Examples
+///
+/// ```
+/// use std::io::Cursor;
+///
+/// use shs_rs::sha256::{sha256, sha256_reader_with_capacity};
+///
+/// let digest = sha256_reader_with_capacity(Cursor::new(b"Hello, world!"), 1 << 20)?;
+/// assert_eq!(digest, sha256(b"Hello, world!"));
+/// # Ok::<(), std::io::Error>(())
+/// ```
+pub fn sha256_reader_with_capacity<R: Read>(reader: R, capacity: usize) -> io::Result<[u8; 32]> {
+    hash_reader(reader, &mut vec![0u8; capacity.max(1).next_multiple_of(BLOCK_SIZE)])
+}
+
+/// Hash a reader to its end, reading into `buffer`.
+fn hash_reader<R: Read>(mut reader: R, buffer: &mut [u8]) -> io::Result<[u8; 32]> {
+    let mut hasher = Sha256::new();
+    loop {
+        match reader.read(buffer) {
+            Ok(0) => return Ok(hasher.finalize()),
+            Ok(n) => hasher.update(&buffer[..n]),
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => {},
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+/// Compute SHA-256 digest of a file's contents.
+///
+/// The file is streamed through [`sha256_reader`], so memory use stays at its 8 KiB buffer
+/// however large the file is.
+///
+/// # Parameters
+///
+/// - `path`: Path of the file to hash.
+///
+/// # Returns
+///
+/// 256-bit digest of the file contents, the digest of the empty message for an empty file, or
+/// the error from opening or reading the file.
+///
+/// # Examples
+///
+/// ```no_run
+/// use shs_rs::sha256::sha256_file;
+///
+/// let digest = sha256_file("Cargo.toml")?;
+/// println!("{:02x?}", digest);
+/// # Ok::<(), std::io::Error>(())
+/// ```
+pub fn sha256_file<P: AsRef<Path>>(path: P) -> io::Result<[u8; 32]> {
+    sha256_reader(File::open(path)?)
+}
+
+/// Compute SHA-256 digest of a file's contents, reading in chunks of a chosen size.
+///
+/// # Parameters
+///
+/// - `path`: Path of the file to hash.
+/// - `capacity`: Size of the read buffer in bytes, see [`sha256_reader_with_capacity`].
+///
+/// # Returns
+///
+/// 256-bit digest of the file contents, or the error from opening or reading the file.
+pub fn sha256_file_with_capacity<P: AsRef<Path>>(path: P, capacity: usize) -> io::Result<[u8; 32]> {
+    sha256_reader_with_capacity(File::open(path)?, capacity)
+}
+
+/// Compute SHA-256 digest of a message produced in pieces by a fallible source.
+///
+/// Like [`sha256_chunks`](super::sha256_chunks), but each piece may be an error, e.g. from a
+/// network stream or a decompressor. Hashing stops at the first error without pulling further
+/// pieces.
+///
+/// # Parameters
+///
+/// - `chunks`: Pieces of the message in order. Empty pieces contribute nothing.
+///
+/// # Returns
+///
+/// 256-bit digest of the concatenation of `chunks`, or the first error they yield, unchanged.
+///
+/// # Examples
+///
+/// ```
+/// use shs_rs::sha256::{sha256, sha256_try_chunks};
+///
+/// let chunks = vec![Ok(b"Hello, ".to_vec()), Ok(b"world!".to_vec())];
+/// assert_eq!(sha256_try_chunks(chunks)?, sha256(b"Hello, world!"));
+/// # Ok::<(), std::io::Error>(())
+/// ```
+pub fn sha256_try_chunks<I: IntoIterator<Item = io::Result<Vec<u8>>>>(
+    chunks: I,
+) -> io::Result<[u8; 32]> {
+    let mut hasher = Sha256::new();
+    for chunk in chunks {
+        hasher.update(&chunk?);
+    }
+    Ok(hasher.finalize())
+}
+
+/// Reader adapter that hashes everything read through it.
+///
+/// Reads are forwarded to the inner reader, and exactly the bytes it returns are fed into a
+/// [`Sha256`]. This way a stream can be consumed, e.g. copied to disk, and checked against a
+/// digest in the same pass.
+///
+/// # Examples
+///
+/// ```
+/// use std::io::{self, Cursor};
+///
+/// use shs_rs::sha256::{sha256, HashingReader};
+///
+/// let mut reader = HashingReader::new(Cursor::new(b"downloaded file"));
+/// let mut file = Vec::new();
+/// io::copy(&mut reader, &mut file)?;
+/// assert_eq!(reader.digest(), sha256(&file));
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[derive(Clone)]
+pub struct HashingReader<R> {
+    inner:  R,
+    hasher: Sha256,
+}
+
+impl<R: Read> HashingReader<R> {
+    /// Wrap a reader, starting with an empty message.
+    ///
+    /// # Parameters
+    ///
+    /// - `inner`: Reader to forward reads to.
+    pub fn new(inner: R) -> Self { Self { inner, hasher: Sha256::new() } }
+
+    /// Digest of the bytes read so far.
+    ///
+    /// Usually called after end of file, but reading may continue afterwards.
+    ///
+    /// # Returns
+    ///
+    /// 256-bit digest of everything returned by [`Read::read`] since the reader was wrapped.
+    #[must_use]
+    pub fn digest(&self) -> [u8; 32] { self.hasher.checkpoint() }
+
+    /// Reference to the inner reader.
+    pub fn get_ref(&self) -> &R { &self.inner }
+
+    /// Unwrap the inner reader, discarding the hash.
+    pub fn into_inner(self) -> R { self.inner }
+}
+
+impl<R: Read> Read for HashingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.hasher.update(&buf[..n]);
+        Ok(n)
+    }
+}
+
+/// Writer adapter that hashes everything written through it.
+///
+/// Writes are forwarded to the inner writer, and only the bytes it accepts are fed into a
+/// [`Sha256`], so short writes are hashed correctly. This way data can be checksummed while it is
+/// serialized to a file or socket.
+///
+/// # Examples
+///
+/// ```
+/// use std::io::Write;
+///
+/// use shs_rs::sha256::{sha256, HashingWriter};
+///
+/// let mut writer = HashingWriter::new(Vec::new());
+/// writer.write_all(b"Hello, ")?;
+/// writer.write_all(b"world!")?;
+/// assert_eq!(writer.digest(), sha256(b"Hello, world!"));
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[derive(Clone)]
+pub struct HashingWriter<W> {
+    inner:  W,
+    hasher: Sha256,
+}
+
+impl<W: Write> HashingWriter<W> {
+    /// Wrap a writer, starting with an empty message.
+    ///
+    /// # Parameters
+    ///
+    /// - `inner`: Writer to forward writes to.
+    pub fn new(inner: W) -> Self { Self { inner, hasher: Sha256::new() } }
+
+    /// Digest of the bytes written so far.
+    ///
+    /// # Returns
+    ///
+    /// 256-bit digest of everything the inner writer accepted since it was wrapped.
+    #[must_use]
+    pub fn digest(&self) -> [u8; 32] { self.hasher.checkpoint() }
+
+    /// Reference to the inner writer.
+    pub fn get_ref(&self) -> &W { &self.inner }
+
+    /// Unwrap the inner writer, discarding the hash.
+    pub fn into_inner(self) -> W { self.inner }
+}
+
+impl<W: Write> Write for HashingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = self.inner.write(buf)?;
+        self.hasher.update(&buf[..n]);
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> { self.inner.flush() }
+}
+
+/// Wrap a writer to hash everything written through it and measure the hashing throughput.
+///
+/// # Parameters
+///
+/// - `inner`: Writer to forward writes to.
+///
+/// # Returns
+///
+/// A [`ProfilingWriter`] around `inner`.
+///
+/// # Examples
+///
+/// ```
+/// use std::io::{self, Write};
+///
+/// use shs_rs::sha256::{active_backend, profile, sha256};
+///
+/// let data = vec![0x5a; 1 << 20];
+/// let mut writer = profile(io::sink());
+/// writer.write_all(&data)?;
+/// assert_eq!(writer.digest(), sha256(&data));
+/// assert_eq!(writer.bytes_processed(), 1 << 20);
+/// println!("{}: {:.0} MB/s", active_backend(), writer.throughput() / 1e6);
+/// # Ok::<(), std::io::Error>(())
+/// ```
+pub fn profile<W: Write>(inner: W) -> ProfilingWriter<W> { ProfilingWriter::new(inner) }
+
+/// Writer adapter that hashes everything written through it, like [`HashingWriter`], and records
+/// how long the hashing took.
+///
+/// Only the time spent in [`Sha256::update`] is measured, not the time the inner writer takes, so
+/// [`ProfilingWriter::throughput`] shows the speed of the hashing backend in a real pipeline. It is
+/// meant to check whether SIMD-accelerated speeds are reached, e.g. against
+/// [`active_backend`](super::active_backend).
+#[derive(Clone)]
+pub struct ProfilingWriter<W> {
+    inner:   W,
+    hasher:  Sha256,
+    bytes:   u64,
+    elapsed: Duration,
+}
+
+impl<W: Write> ProfilingWriter<W> {
+    /// Wrap a writer, starting with an empty message and no time measured.
+    ///
+    /// # Parameters
+    ///
+    /// - `inner`: Writer to forward writes to.
+    pub fn new(inner: W) -> Self {
+        Self { inner, hasher: Sha256::new(), bytes: 0, elapsed: Duration::ZERO }
+    }
+
+    /// Digest of the bytes written so far.
+    ///
+    /// # Returns
+    ///
+    /// 256-bit digest of everything the inner writer accepted since it was wrapped.
+    #[must_use]
+    pub fn digest(&self) -> [u8; 32] { self.hasher.checkpoint() }
+
+    /// Number of bytes hashed so far, i.e. accepted by the inner writer.
+    pub fn bytes_processed(&self) -> u64 { self.bytes }
+
+    /// Total time spent hashing so far.
+    pub fn elapsed(&self) -> Duration { self.elapsed }
+
+    /// Hashing throughput so far.
+    ///
+    /// # Returns
+    ///
+    /// Bytes hashed per second of hashing time, 0 if nothing was written yet. Writes too small for
+    /// the clock to measure can give [`f64::INFINITY`].
+    pub fn throughput(&self) -> f64 {
+        if self.bytes == 0 {
+            return 0.0;
+        }
+        self.bytes as f64 / self.elapsed.as_secs_f64()
+    }
+
+    /// Reference to the inner writer.
+    pub fn get_ref(&self) -> &W { &self.inner }
+
+    /// Unwrap the inner writer, discarding the hash and the measurements.
+    pub fn into_inner(self) -> W { self.inner }
+}
+
+impl<W: Write> Write for ProfilingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = self.inner.write(buf)?;
+        let start = Instant::now();
+        self.hasher.update(&buf[..n]);
+        self.elapsed += start.elapsed();
+        self.bytes += n as u64;
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> { self.inner.flush() }
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::sha256::sha256;
+
+    /// Yields `data` in small pieces, interrupted before each one, then fails if `fail` is set.
+    struct FlakyReader {
+        data:        Vec<u8>,
+        position:    usize,
+        interrupted: bool,
+        fail:        bool,
+    }
+
+    impl Read for FlakyReader {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            self.interrupted = !self.interrupted;
+            if self.interrupted {
+                return Err(io::ErrorKind::Interrupted.into());
+            }
+            if self.position == self.data.len() && self.fail {
+                return Err(io::Error::new(io::ErrorKind::Other, "connection reset"));
+            }
+
+            let n = buf.len().min(1000).min(self.data.len() - self.position);
+            buf[..n].copy_from_slice(&self.data[self.position..self.position + n]);
+            self.position += n;
+            Ok(n)
+        }
+    }
+
+    #[test]
+    fn test_sha256_reader() {
+        let data: Vec<u8> = (0..100 * 1024).map(|i| i as u8).collect();
+        assert_eq!(sha256_reader(Cursor::new(&data)).unwrap(), sha256(&data));
+        assert_eq!(sha256_reader(io::empty()).unwrap(), sha256(b""));
+    }
+
+    #[test]
+    fn test_sha256_reader_with_capacity() {
+        let data: Vec<u8> = (0..10_000).map(|i| i as u8).collect();
+        for capacity in [0, 1, 63, 64, 65, 1000, 1 << 20] {
+            let digest = sha256_reader_with_capacity(Cursor::new(&data), capacity).unwrap();
+            assert_eq!(digest, sha256(&data), "capacity {}", capacity);
+        }
+
+        // Reads are retried and errors passed on as with the default buffer
+        let flaky = FlakyReader {
+            data:        data.clone(),
+            position:    0,
+            interrupted: false,
+            fail:        false,
+        };
+        assert_eq!(sha256_reader_with_capacity(flaky, 100).unwrap(), sha256(&data));
+        let failing = FlakyReader { data, position: 0, interrupted: false, fail: true };
+        let err = sha256_reader_with_capacity(failing, 100).unwrap_err();
+        assert_eq!(err.to_string(), "connection reset");
+    }
+
+    #[test]
+    fn test_sha256_try_chunks() {
+        let data: Vec<u8> = (0..1000).map(|i| i as u8).collect();
+        let chunks = data.chunks(100).map(|chunk| Ok(chunk.to_vec()));
+        assert_eq!(sha256_try_chunks(chunks).unwrap(), sha256(&data));
+        assert_eq!(sha256_try_chunks(Vec::new()).unwrap(), sha256(b""));
+        assert_eq!(
+            sha256_try_chunks([Ok(Vec::new()), Ok(b"abc".to_vec())]).unwrap(),
+            sha256(b"abc")
+        );
+
+        // The first error is returned as is, and nothing after it is pulled
+        let mut pulled = 0;
+        let chunks = (0..10).map(|i| {
+            pulled += 1;
+            match i {
+                3 => Err(io::Error::new(io::ErrorKind::UnexpectedEof, "stream cut")),
+                5 => Err(io::Error::new(io::ErrorKind::Other, "later error")),
+                _ => Ok(Vec::from([i as u8; 10])),
+            }
+        });
+        let err = sha256_try_chunks(chunks).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+        assert_eq!(err.to_string(), "stream cut");
+        assert_eq!(pulled, 4);
+    }
+
+    #[test]
+    fn test_sha256_file() {
+        let dir = std::env::temp_dir().join(format!("shs-rs-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        // Several read buffers long, plus an empty file
+        let data: Vec<u8> = (0..100_000).map(|i| (i * 31) as u8).collect();
+        let path = dir.join("data.bin");
+        std::fs::write(&path, &data).unwrap();
+        assert_eq!(sha256_file(&path).unwrap(), sha256(&data));
+        assert_eq!(sha256_file_with_capacity(&path, 1 << 20).unwrap(), sha256(&data));
+        let empty = dir.join("empty.bin");
+        std::fs::write(&empty, b"").unwrap();
+        assert_eq!(sha256_file(&empty).unwrap(), sha256(b""));
+
+        let err = sha256_file(dir.join("missing.bin")).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_hashing_reader() {
+        let data: Vec<u8> = (0..100 * 1024).map(|i| i as u8).collect();
+        let mut reader = HashingReader::new(Cursor::new(&data));
+        assert_eq!(reader.digest(), sha256(b""));
+
+        // Partial reads are hashed as they happen
+        let mut head = [0u8; 100];
+        reader.read_exact(&mut head).unwrap();
+        assert_eq!(reader.digest(), sha256(&data[..100]));
+
+        let mut rest = Vec::new();
+        reader.read_to_end(&mut rest).unwrap();
+        assert_eq!([&head[..], &rest].concat(), data);
+        assert_eq!(reader.digest(), sha256(&data));
+        assert_eq!(reader.get_ref().position(), data.len() as u64);
+
+        // Only bytes actually read count, through interruptions too
+        let flaky = FlakyReader {
+            data:        data.clone(),
+            position:    0,
+            interrupted: false,
+            fail:        false,
+        };
+        let mut reader = HashingReader::new(flaky);
+        io::copy(&mut reader, &mut io::sink()).unwrap();
+        assert_eq!(reader.digest(), sha256(&data));
+        assert_eq!(reader.into_inner().position, data.len());
+    }
+
+    /// Accepts at most `limit` bytes per write.
+    struct ShortWriter {
+        written: Vec<u8>,
+        limit:   usize,
+    }
+
+    impl Write for ShortWriter {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            let n = buf.len().min(self.limit);
+            self.written.extend_from_slice(&buf[..n]);
+            Ok(n)
+        }
+
+        fn flush(&mut self) -> io::Result<()> { Ok(()) }
+    }
+
+    #[test]
+    fn test_hashing_writer() {
+        let data: Vec<u8> = (0..10_000).map(|i| i as u8).collect();
+        let mut writer = HashingWriter::new(Vec::new());
+        for chunk in data.chunks(777) {
+            writer.write_all(chunk).unwrap();
+        }
+        writer.flush().unwrap();
+        assert_eq!(writer.get_ref(), &data);
+        assert_eq!(writer.digest(), sha256(&data));
+
+        // A short write only hashes what was accepted
+        let mut writer = HashingWriter::new(ShortWriter { written: Vec::new(), limit: 3 });
+        assert_eq!(writer.write(b"Hello, world!").unwrap(), 3);
+        assert_eq!(writer.digest(), sha256(b"Hel"));
+        writer.write_all(b"lo, world!").unwrap();
+        assert_eq!(writer.digest(), sha256(b"Hello, world!"));
+        assert_eq!(writer.into_inner().written, b"Hello, world!");
+    }
+
+    #[test]
+    fn test_profiling_writer() {
+        let mut writer = profile(Vec::new());
+        assert_eq!(writer.bytes_processed(), 0);
+        assert_eq!(writer.throughput(), 0.0);
+
+        let data: Vec<u8> = (0..1 << 20).map(|i| i as u8).collect();
+        for chunk in data.chunks(10_000) {
+            writer.write_all(chunk).unwrap();
+        }
+        assert_eq!(writer.bytes_processed(), data.len() as u64);
+        assert!(writer.elapsed() > Duration::ZERO);
+        assert!(writer.throughput() > 0.0);
+        assert_eq!(writer.digest(), sha256(&data));
+        assert_eq!(writer.get_ref(), &data);
+
+        // Only accepted bytes are counted
+        let mut writer = profile(ShortWriter { written: Vec::new(), limit: 3 });
+        assert_eq!(writer.write(b"Hello, world!").unwrap(), 3);
+        assert_eq!(writer.bytes_processed(), 3);
+        assert_eq!(writer.digest(), sha256(b"Hel"));
+    }
+
+    #[test]
+    fn test_sha256_reader_errors() {
+        let data: Vec<u8> = (0..10_000).map(|i| i as u8).collect();
+
+        let reader = FlakyReader {
+            data:        data.clone(),
+            position:    0,
+            interrupted: false,
+            fail:        false,
+        };
+        assert_eq!(sha256_reader(reader).unwrap(), sha256(&data));
+
+        let reader = FlakyReader { data, position: 0, interrupted: false, fail: true };
+        let err = sha256_reader(reader).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::Other);
+        assert_eq!(err.to_string(), "connection reset");
+    }
+}
@@ -0,0 +1,140 @@
+//! Multi-buffer SHA-256, hashing several messages in parallel lanes of one thread.
+//!
+//! SIMD registers hold the same state word of four messages side by side, so four blocks are
+//! compressed for roughly the price of one. Lanes only stay busy while every message still has
+//! blocks left: inputs of similar length, like Merkle leaves, benefit the most, while the blocks
+//! past the shortest message are finished one message at a time.
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+use super::{backend, x86, Backend};
+use super::{compress_blocks, padding_into, state_to_bytes, BLOCK_SIZE, IHV};
+
+/// A message with its padded tail, addressable block by block.
+struct Lane<'a> {
+    message:     &'a [u8],
+    tail:        [u8; 128],
+    full_blocks: usize,
+    blocks:      usize,
+}
+
+impl<'a> Lane<'a> {
+    fn new(message: &'a [u8]) -> Self {
+        let mut tail = [0u8; 128];
+        let tail_blocks = padding_into(message, &mut tail);
+        let full_blocks = message.len() / BLOCK_SIZE;
+        Self { message, tail, full_blocks, blocks: full_blocks + tail_blocks }
+    }
+
+    /// Block `i` of the padded message.
+    fn block(&self, i: usize) -> &[u8] {
+        match i.checked_sub(self.full_blocks) {
+            None => &self.message[i * BLOCK_SIZE..(i + 1) * BLOCK_SIZE],
+            Some(j) => &self.tail[j * BLOCK_SIZE..(j + 1) * BLOCK_SIZE],
+        }
+    }
+}
+
+/// Whether four lanes of SSE2 beat hashing the messages one by one with the selected backend.
+///
+/// The SHA extensions are faster on a single message than the lanes are on four.
+fn lanes_available() -> bool {
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    {
+        x86::sse2_available()
+            && !matches!(backend::active_backend(), Backend::Scalar | Backend::ShaNi)
+    }
+    #[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+    {
+        false
+    }
+}
+
+/// Compute the SHA-256 digests of four messages at once.
+///
+/// Equivalent to calling [`sha256`](super::sha256) on each input. Blocks up to the length of the
+/// shortest message are compressed in four SIMD lanes, the rest of each message on its own, so
+/// inputs of very different lengths only gain as much as the shortest one allows. Without SIMD
+/// support, or when a faster single-message backend is selected, every message is hashed on its
+/// own.
+///
+/// # Parameters
+///
+/// - `inputs`: Four messages to hash, ideally of equal length.
+///
+/// # Returns
+///
+/// 256-bit digests of `inputs`, in order.
+///
+/// # Examples
+///
+/// ```
+/// use shs_rs::sha256::{sha256, sha256_x4};
+///
+/// let digests = sha256_x4([b"leaf 0", b"leaf 1", b"leaf 2", b"leaf 3"]);
+/// assert_eq!(digests[2], sha256(b"leaf 2"));
+/// ```
+#[must_use]
+pub fn sha256_x4(inputs: [&[u8]; 4]) -> [[u8; 32]; 4] { hash_x4(inputs, lanes_available()) }
+
+/// [`sha256_x4`], with the use of SIMD lanes decided by the caller.
+fn hash_x4(inputs: [&[u8]; 4], use_lanes: bool) -> [[u8; 32]; 4] {
+    let lanes = inputs.map(Lane::new);
+    let mut states = [IHV; 4];
+
+    // Blocks every message still has left go through the lanes
+    let use_lanes = use_lanes && cfg!(any(target_arch = "x86", target_arch = "x86_64"));
+    let shared =
+        if use_lanes { lanes.iter().map(|lane| lane.blocks).min().unwrap_or(0) } else { 0 };
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    for i in 0..shared {
+        let blocks = [lanes[0].block(i), lanes[1].block(i), lanes[2].block(i), lanes[3].block(i)];
+        // SAFETY: `use_lanes` is only set when SSE2 is available
+        unsafe { x86::compress_x4_sse2(&mut states, blocks) };
+    }
+
+    let mut digests = [[0u8; 32]; 4];
+    for ((lane, state), digest) in lanes.iter().zip(states.iter_mut()).zip(digests.iter_mut()) {
+        for i in shared..lane.blocks {
+            compress_blocks(state, lane.block(i));
+        }
+        *digest = state_to_bytes(state);
+    }
+    digests
+}
+
+#[cfg(test)]
+mod test {
+    use rand::Rng;
+
+    use super::*;
+    use crate::{sha256::sha256, test_rng::test_rng};
+
+    fn check(inputs: [&[u8]; 4]) {
+        let expected = inputs.map(sha256);
+        assert_eq!(sha256_x4(inputs), expected);
+        assert_eq!(hash_x4(inputs, false), expected);
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        if x86::sse2_available() {
+            assert_eq!(hash_x4(inputs, true), expected);
+        }
+    }
+
+    #[test]
+    fn test_sha256_x4_same_length() {
+        let mut rng = test_rng();
+        for len in [0, 1, 55, 56, 64, 119, 120, 1000] {
+            let messages: Vec<Vec<u8>> =
+                (0..4).map(|_| (0..len).map(|_| rng.gen()).collect()).collect();
+            check([&messages[0], &messages[1], &messages[2], &messages[3]]);
+        }
+    }
+
+    #[test]
+    fn test_sha256_x4_different_lengths() {
+        let mut rng = test_rng();
+        for lens in [[0, 1, 2, 3], [55, 56, 63, 64], [1, 64, 200, 1000], [1000, 10, 500, 0]] {
+            let messages = lens.map(|len| (0..len).map(|_| rng.gen()).collect::<Vec<u8>>());
+            check([&messages[0], &messages[1], &messages[2], &messages[3]]);
+        }
+    }
+}
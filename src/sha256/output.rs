@@ -0,0 +1,594 @@
+//! Typed SHA-256 digest output.
+
+#[cfg(feature = "alloc")] use alloc::string::String;
+use core::{fmt, str::FromStr};
+
+use subtle::{Choice, ConstantTimeEq};
+
+use crate::encoding::{decode_hex, encode_hex};
+
+/// A 256-bit SHA-256 digest.
+///
+/// Formats as hex and compares in constant time, so `digest == expected` is safe to use for MACs
+/// and commitments. For public digests used as map keys or kept sorted, convert to
+/// [`PublicDigest`].
+///
+/// # Examples
+///
+/// ```
+/// use shs_rs::sha256::sha256_digest;
+///
+/// let digest = sha256_digest(b"abc");
+/// assert_eq!(
+///     format!("{}", digest),
+///     "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+/// );
+/// ```
+#[derive(Clone, Copy, Eq)]
+pub struct Digest(pub [u8; 32]);
+
+impl Digest {
+    /// Encode the digest as unpadded base64url, as used by JWTs.
+    ///
+    /// # Returns
+    ///
+    /// The 43-character encoding using the URL and filename safe `-`/`_` alphabet.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use shs_rs::sha256::sha256_digest;
+    /// assert_eq!(sha256_digest(b"abc").to_base64url(), "ungWv48Bz-pBQUDeXa4iI7ADYaOWF3qctBD_YfIAFa0");
+    /// ```
+    #[cfg(feature = "base64")]
+    pub fn to_base64url(&self) -> String {
+        use base64::Engine;
+        base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(self.0)
+    }
+
+    /// Format the digest as colon-separated hex bytes for humans to compare.
+    ///
+    /// # Returns
+    ///
+    /// 32 groups of two lowercase hex digits joined by `:`, e.g. `ba:78:16:bf:...`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use shs_rs::sha256::sha256_digest;
+    /// assert!(sha256_digest(b"abc").fingerprint().starts_with("ba:78:16:bf:"));
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn fingerprint(&self) -> String {
+        let hex = self.to_hex(false);
+        let mut out = String::with_capacity(32 * 3 - 1);
+        for (i, pair) in hex.chunks_exact(2).enumerate() {
+            if i > 0 {
+                out.push(':');
+            }
+            out.push(pair[0] as char);
+            out.push(pair[1] as char);
+        }
+        out
+    }
+
+    /// Format the digest like OpenSSH prints key fingerprints.
+    ///
+    /// The digest should be the SHA-256 of the public key blob, i.e. the base64-decoded second
+    /// field of an `authorized_keys` line, to match the output of `ssh-keygen -l`.
+    ///
+    /// # Returns
+    ///
+    /// `SHA256:` followed by the unpadded standard base64 encoding of the digest.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use shs_rs::sha256::sha256_digest;
+    /// assert_eq!(
+    ///     sha256_digest(b"abc").openssh_fingerprint(),
+    ///     "SHA256:ungWv48Bz+pBQUDeXa4iI7ADYaOWF3qctBD/YfIAFa0"
+    /// );
+    /// ```
+    #[cfg(feature = "base64")]
+    pub fn openssh_fingerprint(&self) -> String {
+        use base64::Engine;
+        let mut out = String::from("SHA256:");
+        base64::engine::general_purpose::STANDARD_NO_PAD.encode_string(self.0, &mut out);
+        out
+    }
+
+    /// Raw digest bytes.
+    pub fn as_bytes(&self) -> &[u8; 32] { &self.0 }
+
+    /// Count the leading zero bits of the digest read as a big-endian 256-bit integer.
+    ///
+    /// # Returns
+    ///
+    /// A number of bits from 0 to 256, e.g. 8 per leading zero byte.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use shs_rs::sha256::Digest;
+    ///
+    /// let mut bytes = [0u8; 32];
+    /// bytes[2] = 0x10;
+    /// assert_eq!(Digest(bytes).leading_zeros(), 19);
+    /// ```
+    pub fn leading_zeros(&self) -> u32 {
+        let zero_bytes = self.0.iter().take_while(|&&byte| byte == 0).count();
+        match self.0.get(zero_bytes) {
+            Some(byte) => 8 * zero_bytes as u32 + byte.leading_zeros(),
+            None => 256,
+        }
+    }
+
+    /// Check the digest against a proof-of-work style target.
+    ///
+    /// Both are compared as big-endian 256-bit integers. The comparison is not constant time,
+    /// which is fine for public values like block hashes.
+    ///
+    /// # Parameters
+    ///
+    /// - `target`: Largest acceptable digest, big-endian.
+    ///
+    /// # Returns
+    ///
+    /// Whether the digest is less than or equal to `target`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use shs_rs::sha256::Digest;
+    ///
+    /// let mut target = [0xffu8; 32];
+    /// target[0] = 0x00;
+    /// assert!(Digest([0x00; 32]).meets_target(&target));
+    /// assert!(!Digest([0x01; 32]).meets_target(&target));
+    /// ```
+    pub fn meets_target(&self, target: &[u8; 32]) -> bool { self.0 <= *target }
+
+    /// Combine two digests byte by byte with XOR.
+    ///
+    /// XOR is commutative and associative, so combining the digests of a set's elements doesn't
+    /// depend on their order, see [`fold_xor`]. It is **not** a collision-resistant set hash:
+    /// any element added twice cancels out, and sets with the same XOR are easy to find by solving
+    /// linear equations over the digests. Only use it where that is acceptable, e.g. as a cheap
+    /// checksum of public data.
+    ///
+    /// # Parameters
+    ///
+    /// - `other`: Digest to combine with.
+    ///
+    /// # Returns
+    ///
+    /// `self XOR other`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use shs_rs::sha256::sha256_digest;
+    ///
+    /// let (a, b) = (sha256_digest(b"a"), sha256_digest(b"b"));
+    /// assert_eq!(a.xor(&b), b.xor(&a));
+    /// assert_eq!(a.xor(&b).xor(&b), a);
+    /// ```
+    #[must_use]
+    pub fn xor(&self, other: &Digest) -> Digest {
+        let mut bytes = self.0;
+        for (byte, other) in bytes.iter_mut().zip(other.0) {
+            *byte ^= other;
+        }
+        Digest(bytes)
+    }
+
+    /// Hex encode the digest into a caller-provided buffer, without allocating.
+    ///
+    /// Works in `no_std` builds without `alloc`. [`fmt::LowerHex`] writes the same digits straight
+    /// to a [`fmt::Formatter`].
+    ///
+    /// # Parameters
+    ///
+    /// - `out`: Buffer receiving 64 lowercase ASCII hex digits.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use shs_rs::sha256::sha256_digest;
+    ///
+    /// let mut hex = [0u8; 64];
+    /// sha256_digest(b"abc").write_hex(&mut hex);
+    /// assert!(hex.starts_with(b"ba7816bf"));
+    /// ```
+    pub fn write_hex(&self, out: &mut [u8; 64]) { encode_hex(&self.0, out, false); }
+
+    /// Hex encode the digest into a stack buffer.
+    fn to_hex(self, upper: bool) -> [u8; 64] {
+        let mut out = [0u8; 64];
+        encode_hex(&self.0, &mut out, upper);
+        out
+    }
+
+    /// Write the hex encoding, honouring width and alignment flags.
+    fn fmt_hex(&self, f: &mut fmt::Formatter<'_>, upper: bool) -> fmt::Result {
+        let hex = self.to_hex(upper);
+        // Hex digits are always ASCII
+        f.pad(core::str::from_utf8(&hex).map_err(|_| fmt::Error)?)
+    }
+}
+
+/// Combine digests with XOR, independently of their order.
+///
+/// Not a collision-resistant set hash, see [`Digest::xor`].
+///
+/// # Parameters
+///
+/// - `digests`: Digests to combine.
+///
+/// # Returns
+///
+/// XOR of all `digests`, all zeros if there are none.
+///
+/// # Examples
+///
+/// ```
+/// use shs_rs::sha256::{fold_xor, sha256_digest};
+///
+/// let forwards = fold_xor([b"a", b"b", b"c"].map(|item| sha256_digest(item)));
+/// let backwards = fold_xor([b"c", b"b", b"a"].map(|item| sha256_digest(item)));
+/// assert_eq!(forwards, backwards);
+/// ```
+#[must_use]
+pub fn fold_xor<I: IntoIterator<Item = Digest>>(digests: I) -> Digest {
+    digests.into_iter().fold(Digest([0; 32]), |acc, digest| acc.xor(&digest))
+}
+
+impl From<[u8; 32]> for Digest {
+    fn from(bytes: [u8; 32]) -> Self { Self(bytes) }
+}
+
+impl From<Digest> for [u8; 32] {
+    fn from(digest: Digest) -> Self { digest.0 }
+}
+
+impl AsRef<[u8]> for Digest {
+    fn as_ref(&self) -> &[u8] { &self.0 }
+}
+
+impl ConstantTimeEq for Digest {
+    fn ct_eq(&self, other: &Self) -> Choice { self.0.ct_eq(&other.0) }
+}
+
+/// Compares all 32 bytes regardless of where the first mismatch is.
+impl PartialEq for Digest {
+    fn eq(&self, other: &Self) -> bool { self.ct_eq(other).into() }
+}
+
+impl fmt::LowerHex for Digest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { self.fmt_hex(f, false) }
+}
+
+impl fmt::UpperHex for Digest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { self.fmt_hex(f, true) }
+}
+
+/// Lowercase hex, see [`fmt::LowerHex`].
+impl fmt::Display for Digest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { self.fmt_hex(f, false) }
+}
+
+impl fmt::Debug for Digest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { write!(f, "Digest({})", self) }
+}
+
+/// A 256-bit SHA-256 digest of public data, with ordinary comparisons.
+///
+/// Unlike [`Digest`], equality stops at the first differing byte and the type implements [`Ord`]
+/// and [`Hash`](core::hash::Hash), so it can key a `HashMap` or live in a `BTreeSet`. Use it for
+/// content addresses, block hashes and the like. Tags, commitments and anything else an attacker
+/// must not learn byte by byte should stay a [`Digest`].
+///
+/// Digests order as big-endian 256-bit integers, i.e. by their bytes from the first one.
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::HashSet;
+///
+/// use shs_rs::sha256::{sha256_digest, PublicDigest};
+///
+/// let mut seen = HashSet::new();
+/// assert!(seen.insert(PublicDigest::from(sha256_digest(b"chunk"))));
+/// assert!(!seen.insert(PublicDigest::from(sha256_digest(b"chunk"))));
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PublicDigest(pub [u8; 32]);
+
+impl From<Digest> for PublicDigest {
+    fn from(digest: Digest) -> Self { Self(digest.0) }
+}
+
+impl From<PublicDigest> for Digest {
+    fn from(digest: PublicDigest) -> Self { Self(digest.0) }
+}
+
+impl From<[u8; 32]> for PublicDigest {
+    fn from(bytes: [u8; 32]) -> Self { Self(bytes) }
+}
+
+impl AsRef<[u8]> for PublicDigest {
+    fn as_ref(&self) -> &[u8] { &self.0 }
+}
+
+/// Lowercase hex, like [`Digest`].
+impl fmt::Display for PublicDigest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { Digest(self.0).fmt_hex(f, false) }
+}
+
+impl fmt::Debug for PublicDigest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { write!(f, "PublicDigest({})", self) }
+}
+
+/// Parse a digest from 64 hex characters of either case.
+///
+/// Malformed input is rejected without revealing the position of the first invalid character.
+///
+/// # Examples
+///
+/// ```
+/// use shs_rs::sha256::{sha256_digest, Digest};
+///
+/// let expected: Digest =
+///     "BA7816BF8F01CFEA414140DE5DAE2223B00361A396177A9CB410FF61F20015AD".parse()?;
+/// assert_eq!(sha256_digest(b"abc"), expected);
+/// # Ok::<(), shs_rs::sha256::ParseDigestError>(())
+/// ```
+impl FromStr for Digest {
+    type Err = ParseDigestError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.len() != 64 {
+            return Err(ParseDigestError::InvalidLength(s.len()));
+        }
+
+        let mut bytes = [0u8; 32];
+        if !decode_hex(s.as_bytes(), &mut bytes) {
+            return Err(ParseDigestError::InvalidCharacter);
+        }
+        Ok(Self(bytes))
+    }
+}
+
+/// Errors returned when parsing a [`Digest`] from hex.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseDigestError {
+    /// Input is not exactly 64 bytes long.
+    InvalidLength(usize),
+    /// Input contains a character that is not a hex digit.
+    InvalidCharacter,
+}
+
+impl fmt::Display for ParseDigestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseDigestError::InvalidLength(len) =>
+                write!(f, "invalid digest length {}, expected 64 hex characters", len),
+            ParseDigestError::InvalidCharacter => write!(f, "invalid hex character in digest"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseDigestError {}
+
+#[cfg(not(feature = "std"))]
+impl core::error::Error for ParseDigestError {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::sha256::{sha256, sha256_digest};
+    #[cfg(feature = "alloc")]
+    use crate::sha256::{sha256_hex, sha256_hex_upper};
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_digest_format() {
+        let digest = sha256_digest(b"abc");
+        assert_eq!(format!("{}", digest), sha256_hex(b"abc"));
+        assert_eq!(format!("{:x}", digest), sha256_hex(b"abc"));
+        assert_eq!(format!("{:X}", digest), sha256_hex_upper(b"abc"));
+        assert_eq!(format!("{:?}", digest), format!("Digest({})", sha256_hex(b"abc")));
+        assert_eq!(format!("{:>66}", digest), format!("  {}", sha256_hex(b"abc")));
+    }
+
+    #[test]
+    fn test_digest_write_hex() {
+        let digest = sha256_digest(b"abc");
+        let mut out = [0u8; 64];
+        digest.write_hex(&mut out);
+        assert_eq!(&out[..], hex::encode(digest.as_bytes()).as_bytes());
+        assert_eq!(core::str::from_utf8(&out).unwrap(), format!("{:x}", digest));
+    }
+
+    #[test]
+    fn test_digest_conversions() {
+        let bytes = sha256(b"abc");
+        let digest = Digest::from(bytes);
+        assert_eq!(digest.as_ref(), &bytes[..]);
+        assert_eq!(<[u8; 32]>::from(digest), bytes);
+    }
+
+    #[test]
+    fn test_digest_eq() {
+        let digest = sha256_digest(b"abc");
+        assert_eq!(digest, Digest(sha256(b"abc")));
+
+        // Mismatch in the first, a middle, and the last byte
+        for i in [0, 15, 31] {
+            let mut other = digest;
+            other.0[i] ^= 1;
+            assert_ne!(digest, other, "Byte {} not detected", i);
+            assert!(!bool::from(digest.ct_eq(&other)));
+        }
+    }
+
+    #[test]
+    fn test_digest_leading_zeros() {
+        let mut bytes = [0xffu8; 32];
+        assert_eq!(Digest(bytes).leading_zeros(), 0);
+
+        // Three zero bytes, then 0x01
+        bytes[..3].fill(0);
+        bytes[3] = 0x01;
+        assert_eq!(Digest(bytes).leading_zeros(), 24 + 7);
+        bytes[3] = 0x80;
+        assert_eq!(Digest(bytes).leading_zeros(), 24);
+
+        assert_eq!(Digest([0u8; 32]).leading_zeros(), 256);
+        let mut last = [0u8; 32];
+        last[31] = 1;
+        assert_eq!(Digest(last).leading_zeros(), 255);
+        assert_eq!(Digest(last).as_bytes(), &last);
+    }
+
+    #[test]
+    fn test_digest_meets_target() {
+        let mut target = [0u8; 32];
+        target[1] = 0x10;
+        let digest = |first: u8, second: u8, rest: u8| {
+            let mut bytes = [rest; 32];
+            bytes[0] = first;
+            bytes[1] = second;
+            Digest(bytes)
+        };
+
+        assert!(Digest(target).meets_target(&target));
+        assert!(digest(0x00, 0x0f, 0xff).meets_target(&target));
+        assert!(!digest(0x00, 0x10, 0x01).meets_target(&target));
+        assert!(!digest(0x01, 0x00, 0x00).meets_target(&target));
+        assert!(Digest([0u8; 32]).meets_target(&[0u8; 32]));
+    }
+
+    #[test]
+    fn test_digest_xor() {
+        let a = Digest([0x0f; 32]);
+        let b = Digest(core::array::from_fn(|i| i as u8));
+        assert_eq!(a.xor(&b), b.xor(&a));
+        assert_eq!(a.xor(&b).0[1], 0x0e);
+        assert_eq!(a.xor(&a), Digest([0; 32]));
+        assert_eq!(a.xor(&Digest([0; 32])), a);
+
+        let c = Digest([0xa5; 32]);
+        assert_eq!(fold_xor([a, b, c]), fold_xor([c, a, b]));
+        assert_eq!(fold_xor([a, b, c]), a.xor(&b).xor(&c));
+        assert_eq!(fold_xor([a]), a);
+        assert_eq!(fold_xor([]), Digest([0; 32]));
+
+        // Duplicates cancel out, one reason this is no set hash
+        assert_eq!(fold_xor([a, b, b]), a);
+    }
+
+    #[cfg(feature = "base64")]
+    #[test]
+    fn test_digest_to_base64url() {
+        assert_eq!(
+            sha256_digest(b"").to_base64url(),
+            "47DEQpj8HBSa-_TImW-5JCeuQeRkm5NMpJWZG3hSuFU"
+        );
+        assert_eq!(
+            sha256_digest(b"abc").to_base64url(),
+            "ungWv48Bz-pBQUDeXa4iI7ADYaOWF3qctBD_YfIAFa0"
+        );
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_digest_fingerprint() {
+        let digest = sha256_digest(b"abc");
+        let fingerprint = digest.fingerprint();
+        let groups: Vec<&str> = fingerprint.split(':').collect();
+        assert_eq!(groups.len(), 32);
+        assert!(groups.iter().all(|group| group.len() == 2));
+        assert_eq!(groups.concat(), sha256_hex(b"abc"));
+        assert!(fingerprint.starts_with("ba:78:16:bf:") && fingerprint.ends_with(":00:15:ad"));
+    }
+
+    #[cfg(feature = "base64")]
+    #[test]
+    fn test_digest_openssh_fingerprint() {
+        // Blob of "ssh-ed25519
+        // AAAAC3NzaC1lZDI1NTE5AAAAIHnT3OXHCMrUPC9ZxnP+2KbWRH8+gh6ojPhPtPxT5A5U"
+        let blob = hex::decode(
+            "0000000b7373682d6564323535313900000020\
+             79d3dce5c708cad43c2f59c673fed8a6d6447f3e821ea88cf84fb4fc53e40e54",
+        )
+        .unwrap();
+        // As printed by `ssh-keygen -l`
+        assert_eq!(
+            sha256_digest(&blob).openssh_fingerprint(),
+            "SHA256:fycvxj6j0NwTHSnjOiNeDw3CI+7EVFQTnqOeOtG1p3Y"
+        );
+    }
+
+    #[test]
+    fn test_public_digest() {
+        use std::collections::{BTreeSet, HashSet};
+
+        let mut low = [0xffu8; 32];
+        low[0] = 0x00;
+        let mut high = [0x00u8; 32];
+        high[0] = 0x01;
+        let digests = [sha256(b"a"), sha256(b"b"), sha256(b"c"), low, high];
+        let set: BTreeSet<PublicDigest> = digests.iter().copied().map(PublicDigest).collect();
+        let mut sorted = digests.to_vec();
+        sorted.sort();
+        assert_eq!(set.iter().map(|digest| digest.0).collect::<Vec<_>>(), sorted);
+        // Big-endian: the first byte decides before any of the later ones
+        assert!(PublicDigest(low) < PublicDigest(high));
+
+        let digest = sha256_digest(b"abc");
+        let public = PublicDigest::from(digest);
+        assert_eq!(Digest::from(public), digest);
+        assert_eq!(public.as_ref(), digest.as_ref());
+        assert_eq!(public.to_string(), digest.to_string());
+        assert_eq!(format!("{:?}", public), format!("PublicDigest({})", digest));
+        assert!(HashSet::from([public]).contains(&PublicDigest(sha256(b"abc"))));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_digest_from_str() {
+        let hex = sha256_hex(b"abc");
+        assert_eq!(hex.parse(), Ok(sha256_digest(b"abc")));
+        assert_eq!(hex.to_uppercase().parse(), Ok(sha256_digest(b"abc")));
+        assert_eq!(Digest::from_str(&hex).unwrap().to_string(), hex);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_digest_from_str_malformed() {
+        let hex = sha256_hex(b"abc");
+        assert_eq!(Digest::from_str(&hex[..63]), Err(ParseDigestError::InvalidLength(63)));
+        assert_eq!(
+            Digest::from_str(&format!("{}0", hex)),
+            Err(ParseDigestError::InvalidLength(65))
+        );
+        assert_eq!(Digest::from_str(""), Err(ParseDigestError::InvalidLength(0)));
+
+        let with_g = format!("g{}", &hex[1..]);
+        assert_eq!(Digest::from_str(&with_g), Err(ParseDigestError::InvalidCharacter));
+        let with_space = format!("{} ", &hex[..63]);
+        assert_eq!(Digest::from_str(&with_space), Err(ParseDigestError::InvalidCharacter));
+
+        // Length is counted in bytes, a multi-byte character is still not a hex digit
+        let with_multibyte = format!("é{}", &hex[2..]);
+        assert_eq!(Digest::from_str(&with_multibyte), Err(ParseDigestError::InvalidCharacter));
+
+        assert_eq!(
+            ParseDigestError::InvalidLength(63).to_string(),
+            "invalid digest length 63, expected 64 hex characters"
+        );
+    }
+}
@@ -0,0 +1,124 @@
+//! SHA-256 with custom constants and a reduced number of rounds.
+//!
+//! Cryptanalysis results are usually stated for reduced-round variants, e.g. collisions for 24 of
+//! the 64 steps. [`Sha256Params`] makes the initial hash value, the round constants and the round
+//! count configurable, for experiments only: any change other than [`Sha256Params::STANDARD`] is
+//! not SHA-256 and has none of its security.
+//!
+//! # Examples
+//!
+//! ```
+//! use shs_rs::sha256::{sha256, sha256_with_params, Sha256Params};
+//!
+//! let reduced = Sha256Params { rounds: 24, ..Sha256Params::STANDARD };
+//! assert_ne!(sha256_with_params(&reduced, b"abc"), sha256(b"abc"));
+//! ```
+
+use super::{
+    blocks, padding_into, rounds_with, schedule, state_to_bytes, BLOCK_SIZE, IHV, WORDS_K,
+};
+
+/// Parameters of the SHA-256 compression function.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Sha256Params {
+    /// Initial hash value, [`IHV`] for SHA-256.
+    pub iv:     [u32; 8],
+    /// Round constants `K_0..K_63`.
+    pub k:      [u32; 64],
+    /// Number of compression rounds, at most 64.
+    pub rounds: usize,
+}
+
+impl Sha256Params {
+    /// Parameters of standard SHA-256.
+    ///
+    /// See: FIPS 180-4, 4.2.2 and 5.3.3
+    pub const STANDARD: Sha256Params = Sha256Params { iv: IHV, k: WORDS_K, rounds: 64 };
+}
+
+impl Default for Sha256Params {
+    fn default() -> Self { Self::STANDARD }
+}
+
+/// Compute a SHA-256 style digest of a message with custom parameters.
+///
+/// Always runs the portable compression function, as the hardware backends only implement the
+/// standard constants.
+///
+/// # Parameters
+///
+/// - `params`: Initial hash value, round constants and round count.
+/// - `message`: Input message to hash.
+///
+/// # Returns
+///
+/// 256-bit digest of the `message`, equal to [`sha256`](super::sha256) for
+/// [`Sha256Params::STANDARD`].
+///
+/// # Panics
+///
+/// If `params.rounds` exceeds 64.
+#[must_use]
+pub fn sha256_with_params(params: &Sha256Params, message: &[u8]) -> [u8; 32] {
+    assert!(params.rounds <= 64, "SHA-256 has at most 64 rounds");
+
+    let mut hash_value = params.iv;
+    let mut tail = [0u8; 128];
+    let tail_blocks = padding_into(message, &mut tail);
+    for block in blocks(message).chain(blocks(&tail[..tail_blocks * BLOCK_SIZE])) {
+        hash_value = rounds_with(hash_value, &schedule(block), &params.k, params.rounds);
+    }
+    state_to_bytes(&hash_value)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::sha256::{sha256, sha256_with_iv};
+
+    #[test]
+    fn test_standard_params() {
+        for len in [0, 1, 55, 56, 64, 119, 120, 1000] {
+            let message: Vec<u8> = (0..len).map(|i| i as u8).collect();
+            assert_eq!(sha256_with_params(&Sha256Params::STANDARD, &message), sha256(&message));
+            assert_eq!(sha256_with_params(&Sha256Params::default(), &message), sha256(&message));
+        }
+
+        let iv = [1, 2, 3, 4, 5, 6, 7, 8];
+        let params = Sha256Params { iv, ..Sha256Params::STANDARD };
+        assert_eq!(sha256_with_params(&params, b"abc"), sha256_with_iv(iv, b"abc"));
+    }
+
+    #[test]
+    fn test_reduced_rounds() {
+        // Without rounds, the single padding block of "" only adds the state to itself
+        let params = Sha256Params { rounds: 0, ..Sha256Params::STANDARD };
+        assert_eq!(
+            sha256_with_params(&params, b""),
+            state_to_bytes(&IHV.map(|x| x.wrapping_add(x)))
+        );
+
+        // Every round count gives a different function
+        let digests: Vec<[u8; 32]> = (0..=64)
+            .map(|rounds| {
+                sha256_with_params(&Sha256Params { rounds, ..Default::default() }, b"abc")
+            })
+            .collect();
+        for (i, digest) in digests.iter().enumerate() {
+            assert!(!digests[i + 1..].contains(digest));
+        }
+
+        let mut k = WORDS_K;
+        k[63] ^= 1;
+        assert_ne!(
+            sha256_with_params(&Sha256Params { k, ..Default::default() }, b"abc"),
+            sha256(b"abc")
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "at most 64 rounds")]
+    fn test_too_many_rounds() {
+        let _ = sha256_with_params(&Sha256Params { rounds: 65, ..Default::default() }, b"");
+    }
+}
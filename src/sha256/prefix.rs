@@ -0,0 +1,93 @@
+//! Hashing many messages that share a common prefix.
+
+use super::Sha256;
+
+/// Hasher for `sha256(prefix || suffix)` with a fixed prefix and many suffixes.
+///
+/// The prefix is absorbed once when the hasher is created. Each hash only clones the resulting
+/// state, including any partially filled block, and absorbs the suffix. Hashing takes `&self`, so
+/// one hasher can be shared between threads, e.g. in an `Arc`.
+///
+/// # Examples
+///
+/// ```
+/// use shs_rs::sha256::{sha256, PrefixHasher};
+///
+/// let domain = PrefixHasher::new(b"myapp/v1/session/");
+/// assert_eq!(domain.hash(b"1234"), sha256(b"myapp/v1/session/1234"));
+/// ```
+#[derive(Clone)]
+pub struct PrefixHasher {
+    /// Hasher that has absorbed the prefix.
+    midstate: Sha256,
+}
+
+impl PrefixHasher {
+    /// Create a hasher for messages starting with `prefix`.
+    ///
+    /// # Parameters
+    ///
+    /// - `prefix`: Common start of every hashed message.
+    pub fn new(prefix: &[u8]) -> Self {
+        let mut midstate = Sha256::new();
+        midstate.update(prefix);
+        Self { midstate }
+    }
+
+    /// Compute the digest of the prefix followed by `suffix`.
+    ///
+    /// # Parameters
+    ///
+    /// - `suffix`: Rest of the message.
+    ///
+    /// # Returns
+    ///
+    /// `sha256(prefix || suffix)`.
+    #[must_use]
+    pub fn hash(&self, suffix: &[u8]) -> [u8; 32] {
+        let mut hasher = self.hasher();
+        hasher.update(suffix);
+        hasher.finalize()
+    }
+
+    /// Streaming hasher for a suffix given in pieces.
+    ///
+    /// # Returns
+    ///
+    /// A [`Sha256`] that has already absorbed the prefix.
+    pub fn hasher(&self) -> Sha256 { self.midstate.clone() }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{sync::Arc, thread};
+
+    use super::*;
+    use crate::sha256::sha256;
+
+    #[test]
+    fn test_prefix_hasher() {
+        // Not block-aligned, so part of the prefix stays buffered in the midstate
+        let prefix: Vec<u8> = (0..100).collect();
+        let hasher = PrefixHasher::new(&prefix);
+        for suffix in [&b""[..], b"a", &[0x55; 27], &[0x55; 28], &[0xaa; 300]] {
+            assert_eq!(hasher.hash(suffix), sha256(&[&prefix[..], suffix].concat()));
+        }
+        assert_eq!(PrefixHasher::new(b"").hash(b"abc"), sha256(b"abc"));
+    }
+
+    #[test]
+    fn test_prefix_hasher_shared() {
+        let hasher = Arc::new(PrefixHasher::new(b"shared prefix: "));
+        let handles: Vec<_> = (0u8..4)
+            .map(|i| {
+                let hasher = Arc::clone(&hasher);
+                thread::spawn(move || hasher.hash(&[i; 10]))
+            })
+            .collect();
+        for (i, handle) in handles.into_iter().enumerate() {
+            let expected = sha256(&[&b"shared prefix: "[..], &[i as u8; 10]].concat());
+            assert_eq!(handle.join().unwrap(), expected);
+        }
+    }
+}
@@ -0,0 +1,424 @@
+//! SHA-256 compression backends for x86 processors.
+//!
+//! With the SHA extensions, `sha256rnds2` performs two rounds at a time on a state split into
+//! `ABEF` and `CDGH` halves, while `sha256msg1`/`sha256msg2` compute the `sigma0`/`sigma1` terms
+//! of the message schedule four words at a time.
+//!
+//! Without them, AVX2 still speeds up the message schedule by expanding two blocks side by side,
+//! one per 128-bit lane, while the rounds stay scalar. SSE2, which every x86_64 processor has,
+//! expands the schedule of a single block four words at a time.
+//!
+//! # References
+//!
+//! - [Intel SHA Extensions](https://www.intel.com/content/www/us/en/developer/articles/technical/intel-sha-extensions.html)
+//! - [Fast SHA-256 Implementations on Intel Architecture Processors](https://www.intel.com/content/dam/www/public/us/en/documents/white-papers/sha-256-implementations-paper.pdf)
+
+#[cfg(target_arch = "x86")] use core::arch::x86::*;
+#[cfg(target_arch = "x86_64")] use core::arch::x86_64::*;
+
+use super::{blocks, load_block, rounds, schedule, BLOCK_SIZE, WORDS_K, WORDS_K_GROUPED};
+
+/// Check whether the CPU supports the SHA extensions and the SSE versions they are used with.
+///
+/// Without `std` this can only be decided at compile time, e.g. with `-C target-cpu=native`.
+#[inline]
+pub(super) fn sha_ni_available() -> bool {
+    #[cfg(feature = "std")]
+    {
+        std::is_x86_feature_detected!("sha")
+            && std::is_x86_feature_detected!("sse2")
+            && std::is_x86_feature_detected!("ssse3")
+            && std::is_x86_feature_detected!("sse4.1")
+    }
+    #[cfg(not(feature = "std"))]
+    {
+        cfg!(all(
+            target_feature = "sha",
+            target_feature = "sse2",
+            target_feature = "ssse3",
+            target_feature = "sse4.1"
+        ))
+    }
+}
+
+/// Process every complete 512-bit block of `data` with the SHA extensions.
+///
+/// See: FIPS 180-4, 6.2.2
+///
+/// # Parameters
+///
+/// - `hash_value`: Intermediate hash value, updated in place.
+/// - `data`: Message blocks `M^(1)..M^(N)`.
+///
+/// # Safety
+///
+/// The CPU must support the features checked by [`sha_ni_available`].
+#[target_feature(enable = "sha,sse2,ssse3,sse4.1")]
+pub(super) unsafe fn compress_blocks_sha_ni(hash_value: &mut [u32; 8], data: &[u8]) {
+    // Big-endian byte swap of each 32-bit word
+    let bswap = _mm_set_epi64x(0x0c0d_0e0f_0809_0a0b, 0x0405_0607_0001_0203);
+
+    // Rearrange `ABCD`, `EFGH` into the `ABEF`, `CDGH` layout expected by `sha256rnds2`
+    let abcd = _mm_loadu_si128(hash_value.as_ptr() as *const __m128i);
+    let efgh = _mm_loadu_si128(hash_value.as_ptr().add(4) as *const __m128i);
+    let cdab = _mm_shuffle_epi32(abcd, 0xb1);
+    let hgfe = _mm_shuffle_epi32(efgh, 0x1b);
+    let mut abef = _mm_alignr_epi8(cdab, hgfe, 8);
+    let mut cdgh = _mm_blend_epi16(hgfe, cdab, 0xf0);
+
+    for block in data.chunks_exact(BLOCK_SIZE) {
+        let (abef_prev, cdgh_prev) = (abef, cdgh);
+
+        // Message schedule as a ring of the last sixteen words, four per register
+        let mut w = [_mm_setzero_si128(); 4];
+        for g in 0..16 {
+            if g < 4 {
+                // Unaligned load, blocks come straight from caller slices
+                let words = _mm_loadu_si128(block.as_ptr().add(16 * g) as *const __m128i);
+                w[g] = _mm_shuffle_epi8(words, bswap);
+            } else {
+                // W_t = sigma1(W_t-2) + W_t-7 + sigma0(W_t-15) + W_t-16
+                let w_7 = _mm_alignr_epi8(w[(g + 3) % 4], w[(g + 2) % 4], 4);
+                let partial = _mm_add_epi32(_mm_sha256msg1_epu32(w[g % 4], w[(g + 1) % 4]), w_7);
+                w[g % 4] = _mm_sha256msg2_epu32(partial, w[(g + 3) % 4]);
+            }
+
+            // Four rounds, two per `sha256rnds2`
+            let k = _mm_loadu_si128(WORDS_K_GROUPED[g].as_ptr() as *const __m128i);
+            let wk = _mm_add_epi32(w[g % 4], k);
+            cdgh = _mm_sha256rnds2_epu32(cdgh, abef, wk);
+            abef = _mm_sha256rnds2_epu32(abef, cdgh, _mm_shuffle_epi32(wk, 0x0e));
+        }
+
+        // Compute intermediate hash values
+        abef = _mm_add_epi32(abef, abef_prev);
+        cdgh = _mm_add_epi32(cdgh, cdgh_prev);
+    }
+
+    // Back to `ABCD`, `EFGH`
+    let feba = _mm_shuffle_epi32(abef, 0x1b);
+    let dchg = _mm_shuffle_epi32(cdgh, 0xb1);
+    let abcd = _mm_blend_epi16(feba, dchg, 0xf0);
+    let efgh = _mm_alignr_epi8(dchg, feba, 8);
+    _mm_storeu_si128(hash_value.as_mut_ptr() as *mut __m128i, abcd);
+    _mm_storeu_si128(hash_value.as_mut_ptr().add(4) as *mut __m128i, efgh);
+}
+
+/// Check whether the CPU supports AVX2.
+///
+/// Without `std` this can only be decided at compile time, e.g. with `-C target-cpu=native`.
+#[inline]
+pub(super) fn avx2_available() -> bool {
+    #[cfg(feature = "std")]
+    {
+        std::is_x86_feature_detected!("avx2")
+    }
+    #[cfg(not(feature = "std"))]
+    {
+        cfg!(target_feature = "avx2")
+    }
+}
+
+/// Process every complete 512-bit block of `data`, expanding the message schedule with AVX2.
+///
+/// See: FIPS 180-4, 6.2.2
+///
+/// # Parameters
+///
+/// - `hash_value`: Intermediate hash value, updated in place.
+/// - `data`: Message blocks `M^(1)..M^(N)`.
+///
+/// # Safety
+///
+/// The CPU must support the features checked by [`avx2_available`].
+#[target_feature(enable = "avx2")]
+pub(super) unsafe fn compress_blocks_avx2(hash_value: &mut [u32; 8], data: &[u8]) {
+    let mut pairs = data.chunks_exact(2 * BLOCK_SIZE);
+    for pair in pairs.by_ref() {
+        let (first, second) = pair.split_at(BLOCK_SIZE);
+        let [w_first, w_second] = schedule_avx2(first, second);
+        rounds(hash_value, &w_first);
+        rounds(hash_value, &w_second);
+    }
+
+    // An odd block out is not worth a vector schedule
+    if let Some(block) = blocks(pairs.remainder()).next() {
+        rounds(hash_value, &schedule(block));
+    }
+}
+
+/// Rotate each 32-bit lane right by `N`, `SHL` must be `32 - N`.
+#[inline(always)]
+unsafe fn rotr_epi32<const N: i32, const SHL: i32>(x: __m256i) -> __m256i {
+    _mm256_or_si256(_mm256_srli_epi32::<N>(x), _mm256_slli_epi32::<SHL>(x))
+}
+
+/// [`super::sigma0`] of each 32-bit lane.
+#[inline(always)]
+unsafe fn sigma0_epi32(x: __m256i) -> __m256i {
+    let rotations = _mm256_xor_si256(rotr_epi32::<7, 25>(x), rotr_epi32::<18, 14>(x));
+    _mm256_xor_si256(rotations, _mm256_srli_epi32::<3>(x))
+}
+
+/// [`super::sigma1`] of each 32-bit lane.
+#[inline(always)]
+unsafe fn sigma1_epi32(x: __m256i) -> __m256i {
+    let rotations = _mm256_xor_si256(rotr_epi32::<17, 15>(x), rotr_epi32::<19, 13>(x));
+    _mm256_xor_si256(rotations, _mm256_srli_epi32::<10>(x))
+}
+
+/// Expand the message schedules of two 512-bit blocks at once.
+///
+/// The low 128-bit lane holds four words of `first` and the high lane the same four words of
+/// `second`. Four new words are computed per step: `W_t-16`, `W_t-15` and `W_t-7` are already
+/// known for all of them, but `sigma1(W_t-2)` of the upper two depends on the lower two, so it is
+/// added in two halves.
+///
+/// See: FIPS 180-4, 6.2.2
+///
+/// # Parameters
+///
+/// - `first`, `second`: 512-bit message blocks.
+///
+/// # Returns
+///
+/// The sixty-four words `W_0..W_63` of each block.
+///
+/// # Safety
+///
+/// The CPU must support the features checked by [`avx2_available`].
+#[target_feature(enable = "avx2")]
+pub(super) unsafe fn schedule_avx2(first: &[u8], second: &[u8]) -> [[u32; 64]; 2] {
+    assert!(first.len() >= BLOCK_SIZE && second.len() >= BLOCK_SIZE);
+
+    // Big-endian byte swap of each 32-bit word
+    let bswap = _mm256_set_epi64x(
+        0x0c0d_0e0f_0809_0a0b,
+        0x0405_0607_0001_0203,
+        0x0c0d_0e0f_0809_0a0b,
+        0x0405_0607_0001_0203,
+    );
+
+    let mut w = [[0u32; 64]; 2];
+    let mut x = [_mm256_setzero_si256(); 4];
+    for g in 0..16 {
+        if g < 4 {
+            // Unaligned loads, blocks come straight from caller slices
+            let lo = _mm_loadu_si128(first.as_ptr().add(16 * g) as *const __m128i);
+            let hi = _mm_loadu_si128(second.as_ptr().add(16 * g) as *const __m128i);
+            x[g] = _mm256_shuffle_epi8(_mm256_set_m128i(hi, lo), bswap);
+        } else {
+            // W_t-16 + sigma0(W_t-15) + W_t-7
+            let w_15 = _mm256_alignr_epi8::<4>(x[(g + 1) % 4], x[g % 4]);
+            let w_7 = _mm256_alignr_epi8::<4>(x[(g + 3) % 4], x[(g + 2) % 4]);
+            let mut next = _mm256_add_epi32(x[g % 4], _mm256_add_epi32(sigma0_epi32(w_15), w_7));
+
+            // sigma1(W_t-2) for the lower two words, then the upper two from those
+            let w_2 = _mm256_srli_si256::<8>(x[(g + 3) % 4]);
+            next = _mm256_add_epi32(next, sigma1_epi32(w_2));
+            next = _mm256_add_epi32(next, sigma1_epi32(_mm256_slli_si256::<8>(next)));
+            x[g % 4] = next;
+        }
+
+        _mm_storeu_si128(
+            w[0].as_mut_ptr().add(4 * g) as *mut __m128i,
+            _mm256_castsi256_si128(x[g % 4]),
+        );
+        _mm_storeu_si128(
+            w[1].as_mut_ptr().add(4 * g) as *mut __m128i,
+            _mm256_extracti128_si256::<1>(x[g % 4]),
+        );
+    }
+    w
+}
+
+/// Check whether the CPU supports SSE2.
+///
+/// Without `std` this can only be decided at compile time, e.g. with `-C target-cpu=native`.
+#[inline]
+pub(super) fn sse2_available() -> bool {
+    #[cfg(feature = "std")]
+    {
+        std::is_x86_feature_detected!("sse2")
+    }
+    #[cfg(not(feature = "std"))]
+    {
+        cfg!(target_feature = "sse2")
+    }
+}
+
+/// Process every complete 512-bit block of `data`, expanding the message schedule with SSE2.
+///
+/// See: FIPS 180-4, 6.2.2
+///
+/// # Parameters
+///
+/// - `hash_value`: Intermediate hash value, updated in place.
+/// - `data`: Message blocks `M^(1)..M^(N)`.
+///
+/// # Safety
+///
+/// The CPU must support the features checked by [`sse2_available`].
+#[target_feature(enable = "sse2")]
+pub(super) unsafe fn compress_blocks_sse2(hash_value: &mut [u32; 8], data: &[u8]) {
+    for block in blocks(data) {
+        rounds(hash_value, &schedule_sse2(block));
+    }
+}
+
+/// Rotate each 32-bit lane right by `N`, `SHL` must be `32 - N`.
+#[inline(always)]
+unsafe fn rotr_epi32_sse2<const N: i32, const SHL: i32>(x: __m128i) -> __m128i {
+    _mm_or_si128(_mm_srli_epi32::<N>(x), _mm_slli_epi32::<SHL>(x))
+}
+
+/// [`super::sigma0`] of each 32-bit lane.
+#[inline(always)]
+unsafe fn sigma0_epi32_sse2(x: __m128i) -> __m128i {
+    let rotations = _mm_xor_si128(rotr_epi32_sse2::<7, 25>(x), rotr_epi32_sse2::<18, 14>(x));
+    _mm_xor_si128(rotations, _mm_srli_epi32::<3>(x))
+}
+
+/// [`super::sigma1`] of each 32-bit lane.
+#[inline(always)]
+unsafe fn sigma1_epi32_sse2(x: __m128i) -> __m128i {
+    let rotations = _mm_xor_si128(rotr_epi32_sse2::<17, 15>(x), rotr_epi32_sse2::<19, 13>(x));
+    _mm_xor_si128(rotations, _mm_srli_epi32::<10>(x))
+}
+
+/// Expand the message schedule of a 512-bit block four words at a time.
+///
+/// Works like [`schedule_avx2`] on a single 128-bit lane. SSE2 has no byte shuffle, so the
+/// first sixteen words are read as big-endian integers, and no `alignr`, so words straddling two
+/// registers are combined from byte shifts.
+///
+/// See: FIPS 180-4, 6.2.2
+///
+/// # Parameters
+///
+/// - `block`: 512-bit message block.
+///
+/// # Returns
+///
+/// The sixty-four words `W_0..W_63`.
+///
+/// # Safety
+///
+/// The CPU must support the features checked by [`sse2_available`].
+#[target_feature(enable = "sse2")]
+pub(super) unsafe fn schedule_sse2(block: &[u8; BLOCK_SIZE]) -> [u32; 64] {
+    let mut w = [0u32; 64];
+    w[..16].copy_from_slice(&load_block(block));
+
+    let mut x = [_mm_setzero_si128(); 4];
+    for (g, x) in x.iter_mut().enumerate() {
+        *x = _mm_loadu_si128(w.as_ptr().add(4 * g) as *const __m128i);
+    }
+    for g in 4..16 {
+        // W_t-16 + sigma0(W_t-15) + W_t-7
+        let w_15 =
+            _mm_or_si128(_mm_srli_si128::<4>(x[g % 4]), _mm_slli_si128::<12>(x[(g + 1) % 4]));
+        let w_7 =
+            _mm_or_si128(_mm_srli_si128::<4>(x[(g + 2) % 4]), _mm_slli_si128::<12>(x[(g + 3) % 4]));
+        let mut next = _mm_add_epi32(x[g % 4], _mm_add_epi32(sigma0_epi32_sse2(w_15), w_7));
+
+        // sigma1(W_t-2) for the lower two words, then the upper two from those
+        let w_2 = _mm_srli_si128::<8>(x[(g + 3) % 4]);
+        next = _mm_add_epi32(next, sigma1_epi32_sse2(w_2));
+        next = _mm_add_epi32(next, sigma1_epi32_sse2(_mm_slli_si128::<8>(next)));
+        x[g % 4] = next;
+
+        _mm_storeu_si128(w.as_mut_ptr().add(4 * g) as *mut __m128i, next);
+    }
+    w
+}
+
+/// [`super::csigma0`] of each 32-bit lane.
+#[inline(always)]
+unsafe fn csigma0_epi32_sse2(x: __m128i) -> __m128i {
+    let rotations = _mm_xor_si128(rotr_epi32_sse2::<2, 30>(x), rotr_epi32_sse2::<13, 19>(x));
+    _mm_xor_si128(rotations, rotr_epi32_sse2::<22, 10>(x))
+}
+
+/// [`super::csigma1`] of each 32-bit lane.
+#[inline(always)]
+unsafe fn csigma1_epi32_sse2(x: __m128i) -> __m128i {
+    let rotations = _mm_xor_si128(rotr_epi32_sse2::<6, 26>(x), rotr_epi32_sse2::<11, 21>(x));
+    _mm_xor_si128(rotations, rotr_epi32_sse2::<25, 7>(x))
+}
+
+/// Compress one 512-bit block of each of four independent messages.
+///
+/// Every 32-bit lane of a register holds the same variable of a different message, so the
+/// schedule and all sixty-four rounds run on the four messages at once.
+///
+/// See: FIPS 180-4, 6.2.2
+///
+/// # Parameters
+///
+/// - `states`: Intermediate hash values of the four messages, updated in place.
+/// - `blocks`: The next 512-bit block of each message.
+///
+/// # Safety
+///
+/// The CPU must support the features checked by [`sse2_available`].
+#[target_feature(enable = "sse2")]
+pub(super) unsafe fn compress_x4_sse2(states: &mut [[u32; 8]; 4], blocks: [&[u8]; 4]) {
+    let word = |lane: usize, t: usize| {
+        let bytes = &blocks[lane][4 * t..4 * t + 4];
+        u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as i32
+    };
+
+    // Message schedule as a ring of the last sixteen words
+    let mut w = [_mm_setzero_si128(); 16];
+    for (t, w) in w.iter_mut().enumerate() {
+        *w = _mm_set_epi32(word(3, t), word(2, t), word(1, t), word(0, t));
+    }
+
+    let mut vars = [_mm_setzero_si128(); 8];
+    for (i, var) in vars.iter_mut().enumerate() {
+        *var = _mm_set_epi32(
+            states[3][i] as i32,
+            states[2][i] as i32,
+            states[1][i] as i32,
+            states[0][i] as i32,
+        );
+    }
+    let initial = vars;
+    let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = vars;
+
+    for t in 0..64 {
+        if t >= 16 {
+            w[t % 16] = _mm_add_epi32(
+                _mm_add_epi32(sigma1_epi32_sse2(w[(t - 2) % 16]), w[(t - 7) % 16]),
+                _mm_add_epi32(sigma0_epi32_sse2(w[(t - 15) % 16]), w[t % 16]),
+            );
+        }
+
+        let ch = _mm_xor_si128(_mm_and_si128(e, f), _mm_andnot_si128(e, g));
+        let maj = _mm_xor_si128(_mm_and_si128(a, b), _mm_and_si128(c, _mm_xor_si128(a, b)));
+        let k = _mm_set1_epi32(WORDS_K[t] as i32);
+        let temp_1 = _mm_add_epi32(
+            _mm_add_epi32(_mm_add_epi32(h, csigma1_epi32_sse2(e)), _mm_add_epi32(ch, k)),
+            w[t % 16],
+        );
+        let temp_2 = _mm_add_epi32(csigma0_epi32_sse2(a), maj);
+        h = g;
+        g = f;
+        f = e;
+        e = _mm_add_epi32(d, temp_1);
+        d = c;
+        c = b;
+        b = a;
+        a = _mm_add_epi32(temp_1, temp_2);
+    }
+
+    // Compute intermediate hash values, then transpose back into one state per message
+    for (i, var) in [a, b, c, d, e, f, g, h].into_iter().enumerate() {
+        let mut lanes = [0u32; 4];
+        _mm_storeu_si128(lanes.as_mut_ptr() as *mut __m128i, _mm_add_epi32(var, initial[i]));
+        for (state, lane) in states.iter_mut().zip(lanes) {
+            state[i] = lane;
+        }
+    }
+}
@@ -0,0 +1,319 @@
+//! Structure shared by the SHA-2 functions.
+//!
+//! SHA-256 and SHA-512 (with SHA-384) differ only in their word type, the rotation and shift
+//! amounts of the sigma functions, the round constants, the number of rounds and the size of the
+//! length field in the padding. [`sha2_core!`] generates the logical functions, padding, message
+//! schedule and compression rounds from these parameters, so every variant uses one definition of
+//! them.
+//!
+//! See: FIPS 180-4, 4.1, 5.1 and 6
+
+/// Define the SHA-2 core for one word size in the calling module.
+///
+/// Generates private `rotr`, `shr`, `ch`, `maj`, `csigma0`, `csigma1`, `sigma0`, `sigma1`,
+/// `blocks`, `compress_block`, `compress_const`, `schedule`, `expand`, `load_block`, `rounds`,
+/// `rounds_with` and `final_blocks` functions. The logical functions, `compress_const` with the
+/// `expand` and `rounds_with` it runs, and `final_blocks` are `const`, for use in `const`
+/// contexts too.
+///
+/// # Parameters
+///
+/// - `word`: Word type, `u32` or `u64`.
+/// - `length`: Integer type of the bit length at the end of the padding, `u64` or `u128`.
+/// - `block_size`: Size of a message block in bytes.
+/// - `rounds`: Number of rounds, also the length of the message schedule.
+/// - `k`: Round constants, an array of `rounds` words.
+/// - `csigma0`, `csigma1`: Rotation amounts of the upper-case sigma functions.
+/// - `sigma0`, `sigma1`: Rotation amounts then shift amount of the lower-case sigma functions.
+#[rustfmt::skip]
+macro_rules! sha2_core {
+    (
+        word: $word:ty,
+        length: $length:ty,
+        block_size: $block_size:expr,
+        rounds: $rounds:literal,
+        k: $k:expr,
+        csigma0: ($c00:literal, $c01:literal, $c02:literal),
+        csigma1: ($c10:literal, $c11:literal, $c12:literal),
+        sigma0: ($s00:literal, $s01:literal, $s02:literal),
+        sigma1: ($s10:literal, $s11:literal, $s12:literal) $(,)?
+    ) => {
+        /// Rotate right (circular right shift) operation.
+        ///
+        /// See: FIPS 180-4, 3.2
+        ///
+        /// # Parameters
+        ///
+        /// - `x`: `W`-bit word.
+        const fn rotr<const N: u32>(x: $word) -> $word { x.rotate_right(N) }
+
+        /// Shift right operation.
+        ///
+        /// See: FIPS 180-4, 3.2
+        ///
+        /// # Parameters
+        ///
+        /// - `n`: An integer with `0 <= n < W`.
+        const fn shr<const N: u32>(x: $word) -> $word { x.wrapping_shr(N) }
+
+        /// See: FIPS 180-4, 4.1.2 and 4.1.3
+        #[inline(always)]
+        const fn ch(x: $word, y: $word, z: $word) -> $word { (x & y) ^ (!x & z) }
+
+        #[inline(always)]
+        const fn maj(x: $word, y: $word, z: $word) -> $word { (x & y) ^ (x & z) ^ (y & z) }
+
+        const fn csigma0(x: $word) -> $word {
+            rotr::<$c00>(x) ^ rotr::<$c01>(x) ^ rotr::<$c02>(x)
+        }
+
+        const fn csigma1(x: $word) -> $word {
+            rotr::<$c10>(x) ^ rotr::<$c11>(x) ^ rotr::<$c12>(x)
+        }
+
+        const fn sigma0(x: $word) -> $word { rotr::<$s00>(x) ^ rotr::<$s01>(x) ^ shr::<$s02>(x) }
+
+        const fn sigma1(x: $word) -> $word { rotr::<$s10>(x) ^ rotr::<$s11>(x) ^ shr::<$s12>(x) }
+
+        /// Split data into its complete blocks, ignoring a partial block at the end.
+        ///
+        /// Yields array references, so loading a block needs no bounds checks.
+        #[inline(always)]
+        fn blocks(data: &[u8]) -> impl Iterator<Item = &[u8; $block_size]> {
+            // `chunks_exact` only yields full blocks, so the conversion never fails
+            data.chunks_exact($block_size).filter_map(|block| block.try_into().ok())
+        }
+
+        /// Process a single message block, updating the intermediate hash value in place.
+        ///
+        /// See: FIPS 180-4, 6.2.2 and 6.4.2
+        ///
+        /// # Parameters
+        ///
+        /// - `hash_value`: Intermediate hash value `H^(i-1)`, replaced with `H^(i)`.
+        /// - `block`: Message block `M^(i)`.
+        // Always inlined, like `schedule`, so the `no-panic` check of `sha256_nostd` can see
+        // through it
+        #[inline(always)]
+        fn compress_block(hash_value: &mut [$word; 8], block: &[u8; $block_size]) {
+            *hash_value = compress_const(*hash_value, load_block(block));
+        }
+
+        /// Process a single message block given as words, in a `const` context too.
+        ///
+        /// The one definition of the compression function: [`compress_block`] runs it on the
+        /// words of [`load_block`], and `const` callers load the words themselves.
+        ///
+        /// See: FIPS 180-4, 6.2.2 and 6.4.2
+        ///
+        /// # Parameters
+        ///
+        /// - `hash_value`: Intermediate hash value `H^(i-1)`.
+        /// - `words`: The words `M_0^(i)..M_15^(i)` of the message block.
+        ///
+        /// # Returns
+        ///
+        /// The intermediate hash value `H^(i)`.
+        #[inline(always)]
+        const fn compress_const(hash_value: [$word; 8], words: [$word; 16]) -> [$word; 8] {
+            rounds_with(hash_value, &expand(words), &$k, $rounds)
+        }
+
+        /// Expand a message block into the message schedule.
+        ///
+        /// See: FIPS 180-4, 6.2.2 and 6.4.2
+        ///
+        /// # Parameters
+        ///
+        /// - `block`: Message block `M^(i)`.
+        ///
+        /// # Returns
+        ///
+        /// The words `W_0..W_t`, one per round.
+        // Unused by SHA-512, only the SHA-256 backends and parameterized rounds take a schedule
+        #[allow(dead_code)]
+        #[inline(always)]
+        fn schedule(block: &[u8; $block_size]) -> [$word; $rounds] { expand(load_block(block)) }
+
+        /// Expand the words of a message block into the message schedule.
+        ///
+        /// See: FIPS 180-4, 6.2.2 and 6.4.2
+        ///
+        /// # Parameters
+        ///
+        /// - `words`: The words `M_0^(i)..M_15^(i)` of the message block.
+        ///
+        /// # Returns
+        ///
+        /// The words `W_0..W_t`, one per round.
+        #[inline(always)]
+        const fn expand(words: [$word; 16]) -> [$word; $rounds] {
+            let mut w = [0; $rounds];
+
+            // Prepare message schedule
+            let mut t = 0;
+            while t < 16 {
+                w[t] = words[t];
+                t += 1;
+            }
+            // Remaining words
+            while t < $rounds {
+                w[t] = sigma1(w[t - 2])
+                    .wrapping_add(w[t - 7])
+                    .wrapping_add(sigma0(w[t - 15]))
+                    .wrapping_add(w[t - 16]);
+                t += 1;
+            }
+            w
+        }
+
+        /// Divide a block into sixteen big-endian words.
+        ///
+        /// The block is copied into the words as it is, then each word is converted from
+        /// big-endian with `from_be`: a byte swap on little-endian hosts and a no-op on big-endian
+        /// ones, so the result is the same on both.
+        ///
+        /// See: FIPS 180-4, 3.1, 6.2.2 and 6.4.2
+        ///
+        /// # Parameters
+        ///
+        /// - `block`: Message block `M^(i)`.
+        ///
+        /// # Returns
+        ///
+        /// The words `M_0^(i)..M_15^(i)`.
+        #[inline(always)]
+        fn load_block(block: &[u8; $block_size]) -> [$word; 16] {
+            const _: () = assert!(16 * core::mem::size_of::<$word>() == $block_size);
+
+            let mut words = [0; 16];
+            // SAFETY: both are one block long and don't overlap; copying bytes has no alignment
+            // requirement
+            unsafe {
+                core::ptr::copy_nonoverlapping(
+                    block.as_ptr(),
+                    words.as_mut_ptr() as *mut u8,
+                    $block_size,
+                )
+            };
+            for word in words.iter_mut() {
+                *word = <$word>::from_be(*word);
+            }
+            words
+        }
+
+        /// Run all compression rounds, updating the intermediate hash value in place.
+        ///
+        /// See: FIPS 180-4, 6.2.2 and 6.4.2
+        ///
+        /// # Parameters
+        ///
+        /// - `hash_value`: Intermediate hash value `H^(i-1)`, replaced with `H^(i)`.
+        /// - `w`: Message schedule of `M^(i)`, see [`schedule`].
+        // Unused by SHA-512, like `schedule`
+        #[allow(dead_code)]
+        #[inline(always)]
+        fn rounds(hash_value: &mut [$word; 8], w: &[$word; $rounds]) {
+            *hash_value = rounds_with(*hash_value, w, &$k, $rounds);
+        }
+
+        /// Run the first `count` compression rounds with custom round constants.
+        ///
+        /// See: FIPS 180-4, 6.2.2 and 6.4.2
+        ///
+        /// # Parameters
+        ///
+        /// - `hash_value`: Intermediate hash value `H^(i-1)`.
+        /// - `w`: Message schedule of `M^(i)`, see [`schedule`].
+        /// - `k`: Round constants.
+        /// - `count`: Number of rounds, at most the length of `k`.
+        ///
+        /// # Returns
+        ///
+        /// The intermediate hash value `H^(i)`.
+        #[inline(always)]
+        const fn rounds_with(
+            hash_value: [$word; 8],
+            w: &[$word; $rounds],
+            k: &[$word; $rounds],
+            count: usize,
+        ) -> [$word; 8] {
+            // Hash computation
+            let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = hash_value;
+
+            let mut temp_1;
+            let mut temp_2;
+            let mut t = 0;
+            while t < count {
+                temp_1 = h
+                    .wrapping_add(csigma1(e))
+                    .wrapping_add(ch(e, f, g))
+                    .wrapping_add(k[t])
+                    .wrapping_add(w[t]);
+                temp_2 = csigma0(a).wrapping_add(maj(a, b, c));
+                h = g;
+                g = f;
+                f = e;
+                e = d.wrapping_add(temp_1);
+                d = c;
+                c = b;
+                b = a;
+                a = temp_1.wrapping_add(temp_2);
+                t += 1;
+            }
+
+            // Compute intermediate hash values
+            [
+                hash_value[0].wrapping_add(a),
+                hash_value[1].wrapping_add(b),
+                hash_value[2].wrapping_add(c),
+                hash_value[3].wrapping_add(d),
+                hash_value[4].wrapping_add(e),
+                hash_value[5].wrapping_add(f),
+                hash_value[6].wrapping_add(g),
+                hash_value[7].wrapping_add(h),
+            ]
+        }
+
+        /// Pad the final partial block of a message.
+        ///
+        /// See: FIPS 180-4, 5.1.1 and 5.1.2
+        ///
+        /// # Parameters
+        ///
+        /// - `message`: The whole message, used for its final partial block and total length.
+        ///
+        /// # Returns
+        ///
+        /// The padded final block(s) and the number of valid blocks, either 1 or 2.
+        const fn final_blocks(message: &[u8]) -> ([u8; 2 * $block_size], usize) {
+            const LENGTH_SIZE: usize = core::mem::size_of::<$length>();
+
+            let full_len = message.len() - message.len() % $block_size;
+            let rem = message.len() - full_len;
+
+            let mut tail = [0u8; 2 * $block_size];
+            let mut i = 0;
+            while i < rem {
+                tail[i] = message[full_len + i];
+                i += 1;
+            }
+
+            // Append "1" bit to the end of message
+            tail[rem] = 0x80;
+
+            // Append length as big-endian integer, spilling into a second block if needed
+            let tail_blocks = if rem < $block_size - LENGTH_SIZE { 1 } else { 2 };
+            let length_bytes = ((message.len() as $length) * 8).to_be_bytes();
+            i = 0;
+            while i < LENGTH_SIZE {
+                tail[tail_blocks * $block_size - LENGTH_SIZE + i] = length_bytes[i];
+                i += 1;
+            }
+
+            (tail, tail_blocks)
+        }
+    };
+}
+
+pub(crate) use sha2_core;
@@ -0,0 +1,341 @@
+//! SHA-512 and SHA-384 implementations based on FIPS 180-4 specification.
+//!
+//! SHA-512 shares its logical functions, padding, message schedule and rounds with
+//! [`crate::sha256`], instantiated with 64-bit words, 80 rounds, different rotation amounts, and
+//! 1024-bit blocks whose padding ends in a 128-bit length.
+//!
+//! # References
+//!
+//! - [FIPS 180-4 Specification](https://nvlpubs.nist.gov/nistpubs/FIPS/NIST.FIPS.180-4.pdf)
+//!
+//! # Examples
+//!
+//! ```
+//! use shs_rs::sha512::sha512;
+//!
+//! let message = b"Hello, world!";
+//! let digest = sha512(message);
+//! println!("SHA-512 digest: {:x?}", digest);
+//! ```
+
+use crate::{
+    hash::{HashFunction, OutputSize},
+    sha2_core::sha2_core,
+};
+
+// Logical functions, padding, message schedule and rounds with the SHA-512 parameters.
+//
+// See: FIPS 180-4, 4.1.3, 5.1.2 and 6.4.2
+sha2_core! {
+    word: u64,
+    length: u128,
+    block_size: BLOCK_SIZE,
+    rounds: 80,
+    k: WORDS_K,
+    csigma0: (28, 34, 39),
+    csigma1: (14, 18, 41),
+    sigma0: (1, 8, 7),
+    sigma1: (19, 61, 6),
+}
+
+/// `WORDS_K`, also known as "round constants", represent the first sixty-four bits of the
+/// fractional parts of the cube roots of the first eighty prime numbers.
+///
+/// See: FIPS 180-4, 4.2.3
+const WORDS_K: [u64; 80] = [
+    0x428a2f98d728ae22,
+    0x7137449123ef65cd,
+    0xb5c0fbcfec4d3b2f,
+    0xe9b5dba58189dbbc,
+    0x3956c25bf348b538,
+    0x59f111f1b605d019,
+    0x923f82a4af194f9b,
+    0xab1c5ed5da6d8118,
+    0xd807aa98a3030242,
+    0x12835b0145706fbe,
+    0x243185be4ee4b28c,
+    0x550c7dc3d5ffb4e2,
+    0x72be5d74f27b896f,
+    0x80deb1fe3b1696b1,
+    0x9bdc06a725c71235,
+    0xc19bf174cf692694,
+    0xe49b69c19ef14ad2,
+    0xefbe4786384f25e3,
+    0x0fc19dc68b8cd5b5,
+    0x240ca1cc77ac9c65,
+    0x2de92c6f592b0275,
+    0x4a7484aa6ea6e483,
+    0x5cb0a9dcbd41fbd4,
+    0x76f988da831153b5,
+    0x983e5152ee66dfab,
+    0xa831c66d2db43210,
+    0xb00327c898fb213f,
+    0xbf597fc7beef0ee4,
+    0xc6e00bf33da88fc2,
+    0xd5a79147930aa725,
+    0x06ca6351e003826f,
+    0x142929670a0e6e70,
+    0x27b70a8546d22ffc,
+    0x2e1b21385c26c926,
+    0x4d2c6dfc5ac42aed,
+    0x53380d139d95b3df,
+    0x650a73548baf63de,
+    0x766a0abb3c77b2a8,
+    0x81c2c92e47edaee6,
+    0x92722c851482353b,
+    0xa2bfe8a14cf10364,
+    0xa81a664bbc423001,
+    0xc24b8b70d0f89791,
+    0xc76c51a30654be30,
+    0xd192e819d6ef5218,
+    0xd69906245565a910,
+    0xf40e35855771202a,
+    0x106aa07032bbd1b8,
+    0x19a4c116b8d2d0c8,
+    0x1e376c085141ab53,
+    0x2748774cdf8eeb99,
+    0x34b0bcb5e19b48a8,
+    0x391c0cb3c5c95a63,
+    0x4ed8aa4ae3418acb,
+    0x5b9cca4f7763e373,
+    0x682e6ff3d6b2b8a3,
+    0x748f82ee5defb2fc,
+    0x78a5636f43172f60,
+    0x84c87814a1f0ab72,
+    0x8cc702081a6439ec,
+    0x90befffa23631e28,
+    0xa4506cebde82bde9,
+    0xbef9a3f7b2c67915,
+    0xc67178f2e372532b,
+    0xca273eceea26619c,
+    0xd186b8c721c0c207,
+    0xeada7dd6cde0eb1e,
+    0xf57d4f7fee6ed178,
+    0x06f067aa72176fba,
+    0x0a637dc5a2c898a6,
+    0x113f9804bef90dae,
+    0x1b710b35131c471b,
+    0x28db77f523047d84,
+    0x32caab7b40c72493,
+    0x3c9ebe0a15c9bebc,
+    0x431d67c49c100d4c,
+    0x4cc5d4becb3e42b6,
+    0x597f299cfc657e2a,
+    0x5fcb6fab3ad6faec,
+    0x6c44198c4a475817,
+];
+
+/// Initial hash value.
+///
+/// See: FIPS 180-4, 5.3.5
+pub const IHV: [u64; 8] = [
+    0x6a09e667f3bcc908,
+    0xbb67ae8584caa73b,
+    0x3c6ef372fe94f82b,
+    0xa54ff53a5f1d36f1,
+    0x510e527fade682d1,
+    0x9b05688c2b3e6c1f,
+    0x1f83d9abfb41bd6b,
+    0x5be0cd19137e2179,
+];
+
+/// Initial hash value of SHA-384.
+///
+/// See: FIPS 180-4, 5.3.4
+pub const IHV_384: [u64; 8] = [
+    0xcbbb9d5dc1059ed8,
+    0x629a292a367cd507,
+    0x9159015a3070dd17,
+    0x152fecd8f70e5939,
+    0x67332667ffc00b31,
+    0x8eb44a8768581511,
+    0xdb0c2e0d64f98fa7,
+    0x47b5481dbefa4fa4,
+];
+
+/// Size of a SHA-512 message block in bytes.
+pub const BLOCK_SIZE: usize = 128;
+
+/// Hash a whole message starting from `initial_state`.
+///
+/// See: FIPS 180-4, 5.1.2
+///
+/// # Parameters
+///
+/// - `initial_state`: Hash value to start from, e.g. [`IHV`].
+/// - `message`: Input message to hash.
+///
+/// # Returns
+///
+/// The final hash value `H^(N)`.
+fn hash_message(initial_state: [u64; 8], message: &[u8]) -> [u64; 8] {
+    let mut hash_value = initial_state;
+
+    // Divide the message into 1024-bit blocks, then pad the final partial block
+    for block in blocks(message) {
+        compress_block(&mut hash_value, block);
+    }
+    let (tail, tail_blocks) = final_blocks(message);
+    for block in blocks(&tail[..tail_blocks * BLOCK_SIZE]) {
+        compress_block(&mut hash_value, block);
+    }
+
+    hash_value
+}
+
+/// Compute SHA-512 digest of a message.
+///
+/// # Parameters
+///
+/// - `message`: Input message to hash.
+///
+/// # Returns
+///
+/// 512-bit digest of the `message`.
+///
+/// # Examples
+///
+/// ```
+/// use shs_rs::sha512::sha512;
+/// let message = b"Hello, world!";
+/// let digest = sha512(message);
+/// println!("SHA-512 digest: {:x?}", digest);
+/// ```
+#[must_use]
+pub fn sha512(message: &[u8]) -> [u8; 64] {
+    let hash_value = hash_message(IHV, message);
+
+    let mut result = [0u8; 64];
+    for (bytes, word) in result.chunks_exact_mut(8).zip(hash_value) {
+        bytes.copy_from_slice(&word.to_be_bytes());
+    }
+    result
+}
+
+/// Compute SHA-384 digest of a message.
+///
+/// SHA-384 is SHA-512 started from [`IHV_384`] with the output truncated to its first six words.
+///
+/// See: FIPS 180-4, 6.5
+///
+/// # Parameters
+///
+/// - `message`: Input message to hash.
+///
+/// # Returns
+///
+/// 384-bit digest of the `message`.
+///
+/// # Examples
+///
+/// ```
+/// use shs_rs::sha512::sha384;
+/// let message = b"Hello, world!";
+/// let digest = sha384(message);
+/// println!("SHA-384 digest: {:x?}", digest);
+/// ```
+#[must_use]
+pub fn sha384(message: &[u8]) -> [u8; 48] {
+    let hash_value = hash_message(IHV_384, message);
+
+    let mut result = [0u8; 48];
+    for (bytes, word) in result.chunks_exact_mut(8).zip(hash_value) {
+        bytes.copy_from_slice(&word.to_be_bytes());
+    }
+    result
+}
+
+/// SHA-512 as a [`HashFunction`], for code generic over the hash.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Sha512;
+
+impl OutputSize for Sha512 {
+    const OUTPUT_SIZE: usize = 64;
+}
+
+impl HashFunction for Sha512 {
+    type Output = [u8; 64];
+
+    fn digest(input: &[u8]) -> [u8; 64] { sha512(input) }
+}
+
+/// SHA-384 as a [`HashFunction`], for code generic over the hash.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Sha384;
+
+impl OutputSize for Sha384 {
+    const OUTPUT_SIZE: usize = 48;
+}
+
+impl HashFunction for Sha384 {
+    type Output = [u8; 48];
+
+    fn digest(input: &[u8]) -> [u8; 48] { sha384(input) }
+}
+
+#[cfg(test)]
+mod test {
+    use sha2::Digest;
+
+    use super::*;
+
+    #[test]
+    fn test_sha512() {
+        let test_cases = [
+            (
+                "",
+                "cf83e1357eefb8bdf1542850d66d8007d620e4050b5715dc83f4a921d36ce9ce47d0d13c5d85f2b0ff8318d2877eec2f63b931bd47417a81a538327af927da3e",
+            ),
+            (
+                "abc",
+                "ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f",
+            ),
+            (
+                "abcdefghbcdefghicdefghijdefghijkefghijklfghijklmghijklmnhijklmnoijklmnopjklmnopqklmnopqrlmnopqrsmnopqrstnopqrstu",
+                "8e959b75dae313da8cf4f72814fc143f8f7779c6eb9f7fa17299aeadb6889018501d289e4900f7e4331b99dec4b5433ac7d329eeb6dd26545e96e55b874be909",
+            ),
+        ];
+
+        for (input, expected) in test_cases.iter() {
+            let result = sha512(input.as_bytes());
+            assert_eq!(hex::encode(result), *expected);
+        }
+    }
+
+    #[test]
+    fn test_sha384() {
+        let test_cases = [
+            (
+                "",
+                "38b060a751ac96384cd9327eb1b1e36a21fdb71114be07434c0cc7bf63f6e1da274edebfe76f65fbd51ad2f14898b95b",
+            ),
+            (
+                "abc",
+                "cb00753f45a35e8bb5a03d699ac65007272c32ab0eded1631a8b605a43ff5bed8086072ba1e7cc2358baeca134c825a7",
+            ),
+            (
+                "abcdefghbcdefghicdefghijdefghijkefghijklfghijklmghijklmnhijklmnoijklmnopjklmnopqklmnopqrlmnopqrsmnopqrstnopqrstu",
+                "09330c33f71147e83d192fc782cd1b4753111b173b3b05d22fa08086e3b0f712fcc7c71a557e2db966c3e9fa91746039",
+            ),
+        ];
+
+        for (input, expected) in test_cases.iter() {
+            let result = sha384(input.as_bytes());
+            assert_eq!(hex::encode(result), *expected);
+        }
+    }
+
+    #[test]
+    fn test_sha512_padding_boundaries() {
+        // One- and two-block padding, and multi-block messages
+        for len in [1, 111, 112, 127, 128, 129, 239, 240, 1000] {
+            let message: Vec<u8> = (0..len).map(|i| i as u8).collect();
+            assert_eq!(
+                sha512(&message)[..],
+                sha2::Sha512::digest(&message)[..],
+                "Length {} failed",
+                len
+            );
+        }
+    }
+}
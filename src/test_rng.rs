@@ -0,0 +1,41 @@
+//! Deterministic random number generator for tests of randomized APIs.
+//!
+//! Tests of the `rand`-gated features and randomized comparison tests draw from a ChaCha20
+//! generator seeded with [`TEST_SEED`] rather than the OS, so their outputs can be pinned as test
+//! vectors and a failure seen once is seen on every run.
+
+use rand_chacha::{rand_core::SeedableRng, ChaCha20Rng};
+
+/// Seed of [`test_rng`], fixed so test vectors stay valid.
+pub(crate) const TEST_SEED: [u8; 32] = *b"shs-rs deterministic test seed!!";
+
+/// Create a fresh generator seeded with [`TEST_SEED`].
+///
+/// # Returns
+///
+/// A ChaCha20 generator producing the same stream on every call.
+pub(crate) fn test_rng() -> ChaCha20Rng { ChaCha20Rng::from_seed(TEST_SEED) }
+
+#[cfg(test)]
+mod test {
+    use rand_chacha::rand_core::RngCore;
+
+    use super::*;
+
+    #[test]
+    fn test_test_rng_stream() {
+        let mut first = [0u8; 32];
+        test_rng().fill_bytes(&mut first);
+        assert_eq!(
+            hex::encode(first),
+            "e7bd29cae52b1a378d2a8921ffce4d4a12ae02146ba7756583ca88758f5a5b26"
+        );
+
+        let mut rng = test_rng();
+        let mut again = [0u8; 32];
+        rng.fill_bytes(&mut again);
+        assert_eq!(again, first);
+        rng.fill_bytes(&mut again);
+        assert_ne!(again, first);
+    }
+}
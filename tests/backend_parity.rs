@@ -0,0 +1,64 @@
+//! Every compression backend this CPU supports must agree with the portable one.
+//!
+//! SIMD backends only run on CPUs with the matching features, so a bug in one of them can go
+//! unnoticed on machines without it. This test compares each backend to the scalar one on the same
+//! inputs, and skips, rather than fails, those the current CPU can't run.
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use shs_rs::sha256::{
+    compute_hash_with_backend, padding, set_backend_override, sha256, sha256_x4, Backend, Sha256,
+    IHV,
+};
+
+const SEED: u64 = 0x5eed;
+
+fn random_inputs() -> Vec<Vec<u8>> {
+    let mut rng = StdRng::seed_from_u64(SEED);
+    (0..256)
+        .map(|_| {
+            let len = rng.gen_range(0..200);
+            (0..len).map(|_| rng.gen()).collect()
+        })
+        .collect()
+}
+
+#[test]
+fn backends_match_scalar() {
+    let inputs = random_inputs();
+    let expected: Vec<[u8; 32]> = inputs
+        .iter()
+        .map(|input| compute_hash_with_backend(Backend::Scalar, IHV, &padding(input)).unwrap())
+        .collect();
+
+    for backend in Backend::ALL {
+        if !backend.is_available() {
+            eprintln!("skipping {} backend, not supported by this CPU", backend);
+            continue;
+        }
+
+        // The backend directly, and every API routed through it by the override
+        set_backend_override(Some(backend)).unwrap();
+        for (input, expected) in inputs.iter().zip(&expected) {
+            let name =
+                format!("{} backend, {} byte input (seed {:#x})", backend, input.len(), SEED);
+            assert_eq!(
+                compute_hash_with_backend(backend, IHV, &padding(input)).as_ref(),
+                Ok(expected),
+                "{}",
+                name
+            );
+            assert_eq!(&sha256(input), expected, "sha256 on {}", name);
+
+            let mut hasher = Sha256::new();
+            for chunk in input.chunks(37) {
+                hasher.update(chunk);
+            }
+            assert_eq!(&hasher.finalize(), expected, "Sha256 on {}", name);
+        }
+        for (group, expected) in inputs.chunks_exact(4).zip(expected.chunks_exact(4)) {
+            let digests = sha256_x4([&group[0][..], &group[1], &group[2], &group[3]]);
+            assert_eq!(digests, expected, "sha256_x4 on {} backend", backend);
+        }
+    }
+    set_backend_override(None).unwrap();
+}
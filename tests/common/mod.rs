@@ -0,0 +1,40 @@
+//! Helpers shared by the SHA_d-256 tests and the `gen_vectors` example.
+
+use shs_rs::sha256::{sha256, sha256d};
+
+// Simple RC4 implementation for test vector generation
+pub fn rc4_keystream(length: usize) -> Vec<u8> {
+    let mut s: Vec<u8> = (0..=255).collect();
+    let mut j: u8 = 0;
+    for i in 0..256 {
+        // Key is all zeros
+        j = j.wrapping_add(s[i]).wrapping_add(0);
+        s.swap(i, j as usize);
+    }
+    let mut i: u8 = 0;
+    j = 0;
+    let mut result = Vec::with_capacity(length);
+    for _ in 0..length {
+        i = i.wrapping_add(1);
+        j = j.wrapping_add(s[i as usize]);
+        s.swap(i as usize, j as usize);
+        let k = s[(s[i as usize].wrapping_add(s[j as usize])) as usize];
+        result.push(k);
+    }
+    result
+}
+
+/// Format a vector as a line of `SHAd256_Test_Vectors.txt`.
+///
+/// The line holds the identifier, the input length, the input field (hex data, or e.g. `RC4` for
+/// a keystream prefix), and the SHA-256 and SHA_d-256 digests of `input`.
+pub fn format_vector(identifier: &str, data_field: &str, input: &[u8]) -> String {
+    format!(
+        ":{} {} {} {} {}",
+        identifier,
+        input.len(),
+        data_field,
+        hex::encode(sha256(input)),
+        hex::encode(sha256d(input))
+    )
+}
@@ -0,0 +1,101 @@
+//! Constant-time regression tests running scenarios of the dudect bench in `examples/`.
+//!
+//! Only scenarios whose classes share the message length are checked: `length_dependent_timing`
+//! compares messages of different block counts, which take different time by design since the
+//! length is public. `content_across_lengths` checks the contents at each of those lengths instead.
+//!
+//! Timing measurements are slow and sensitive to machine load, so these tests are ignored by
+//! default. Run them on an otherwise idle machine with:
+//!
+//! ```bash
+//! cargo test --release --test constant_time -- --ignored --test-threads=1
+//! ```
+
+use std::{fs, path::PathBuf};
+
+use ct_scenarios::{content_across_lengths, single_bit_difference, OURS};
+use dudect_bencher::{
+    ctbench::{run_benches_console, BenchMetadata, BenchName, BenchOpts},
+    BenchRng, CtRunner,
+};
+
+#[path = "../examples/ct_scenarios/mod.rs"]
+mod ct_scenarios;
+
+const SEED: Option<u64> = Some(0xdeadbeef);
+
+/// Largest Welch's t-value accepted, dudect considers `|t| > 5` a likely timing leak.
+const T_THRESHOLD: f64 = 5.0;
+
+/// Welch's t-value of two samples.
+fn welch_t(left: &[f64], right: &[f64]) -> f64 {
+    let mean = |xs: &[f64]| xs.iter().sum::<f64>() / xs.len() as f64;
+    let var = |xs: &[f64], m: f64| {
+        xs.iter().map(|x| (x - m).powi(2)).sum::<f64>() / (xs.len() - 1) as f64
+    };
+
+    let (mean_left, mean_right) = (mean(left), mean(right));
+    let (var_left, var_right) = (var(left, mean_left), var(right, mean_right));
+    (mean_left - mean_right)
+        / (var_left / left.len() as f64 + var_right / right.len() as f64).sqrt()
+}
+
+/// Run a dudect bench and compute its largest t-value.
+///
+/// `dudect-bencher` only prints its statistics, so the raw runtimes are written to a CSV file and
+/// the t-test is repeated here the same way: on all samples, and on the samples below each of the
+/// percentiles dudect crops at to discard measurements disturbed by interrupts.
+fn max_t(name: &'static str, benchfn: fn(&mut CtRunner, &mut BenchRng)) -> f64 {
+    let file_out: PathBuf = std::env::temp_dir().join(format!("shs_rs_ct_{}.csv", name));
+    let opts =
+        BenchOpts { continuous: false, filter: None, file_out: Some(file_out.clone()) };
+    let bench = BenchMetadata { name: BenchName(name), seed: SEED, benchfn };
+    run_benches_console(opts, vec![bench]).unwrap();
+
+    // Rows alternate between the left and right class after the header
+    let csv = fs::read_to_string(&file_out).unwrap();
+    fs::remove_file(&file_out).unwrap();
+    let runtimes: Vec<f64> = csv
+        .lines()
+        .skip(1)
+        .filter(|line| !line.is_empty())
+        .map(|line| line.rsplit(',').next().unwrap().parse().unwrap())
+        .collect();
+    let (left, right): (Vec<_>, Vec<_>) = runtimes.chunks_exact(2).map(|r| (r[0], r[1])).unzip();
+
+    let mut sorted = runtimes.clone();
+    sorted.sort_by(f64::total_cmp);
+    let thresholds = (0..100).map(|i| {
+        let pct = 1.0 - 0.5f64.powf(10.0 * (i + 1) as f64 / 100.0);
+        sorted[((sorted.len() - 1) as f64 * pct) as usize]
+    });
+
+    let mut max_t = welch_t(&left, &right);
+    for threshold in thresholds {
+        let crop = |xs: &[f64]| xs.iter().copied().filter(|&x| x < threshold).collect::<Vec<_>>();
+        let (left, right) = (crop(&left), crop(&right));
+        if left.len() < 2 || right.len() < 2 {
+            continue;
+        }
+        let t = welch_t(&left, &right);
+        if t.abs() > max_t.abs() {
+            max_t = t;
+        }
+    }
+    max_t
+}
+
+#[test]
+#[ignore]
+fn content_across_lengths_is_constant() {
+    let t =
+        max_t("content_across_lengths", |runner, rng| content_across_lengths(runner, rng, &OURS));
+    assert!(t.abs() < T_THRESHOLD, "max t = {}", t);
+}
+
+#[test]
+#[ignore]
+fn single_bit_difference_is_constant() {
+    let t = max_t("single_bit_difference", |runner, rng| single_bit_difference(runner, rng, &OURS));
+    assert!(t.abs() < T_THRESHOLD, "max t = {}", t);
+}
@@ -0,0 +1,36 @@
+//! Property-based comparison of `shs_rs` against the `sha2` crate.
+
+use proptest::{collection::vec, prelude::*};
+use sha2::Digest;
+use shs_rs::sha256::{sha256, Sha256};
+
+fn reference(message: &[u8]) -> [u8; 32] { sha2::Sha256::digest(message).into() }
+
+/// Lengths around block boundaries and the point where padding spills into another block.
+fn boundary_len() -> impl Strategy<Value = usize> {
+    (prop::sample::select(vec![55usize, 56, 63, 64, 119, 120]), -1isize..=1)
+        .prop_map(|(len, delta)| (len as isize + delta) as usize)
+}
+
+proptest! {
+    #[test]
+    fn sha256_matches_sha2(message in vec(any::<u8>(), 0..=4096)) {
+        prop_assert_eq!(sha256(&message), reference(&message));
+    }
+
+    #[test]
+    fn sha256_matches_sha2_near_block_boundaries(
+        message in boundary_len().prop_flat_map(|len| vec(any::<u8>(), len))
+    ) {
+        prop_assert_eq!(sha256(&message), reference(&message));
+    }
+
+    #[test]
+    fn streaming_matches_sha2(message in vec(any::<u8>(), 0..=1024), chunk_size in 1usize..=200) {
+        let mut hasher = Sha256::new();
+        for chunk in message.chunks(chunk_size) {
+            hasher.update(chunk);
+        }
+        prop_assert_eq!(hasher.finalize(), reference(&message));
+    }
+}
@@ -0,0 +1,198 @@
+//! HMAC-SHA256 and HKDF-SHA256 against test vectors in the Project Wycheproof JSON format.
+//!
+//! The upstream Wycheproof suites are vendored in `tests/data/wycheproof/`. Small suites in the
+//! same format, built from the RFC 4231 and RFC 5869 vectors, are in `tests/data/rfc/`.
+//!
+//! Every failing case is reported with its `tcId` and comment, not only the first one.
+
+use std::{fs, path::Path};
+
+use serde::Deserialize;
+use shs_rs::{
+    hkdf::{hkdf_expand, hkdf_extract},
+    hmac::HmacSha256,
+    mac::Mac,
+};
+
+const DATA_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/data");
+
+/// A test vector file, `testGroups` holding the groups of one test type.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Suite<G> {
+    algorithm:   String,
+    test_groups: Vec<G>,
+}
+
+/// Expected outcome of a test case.
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "lowercase")]
+enum Outcome {
+    Valid,
+    Invalid,
+    /// Either outcome is allowed, e.g. for legacy parameters.
+    Acceptable,
+}
+
+impl Outcome {
+    fn allows(self, accepted: bool) -> bool {
+        match self {
+            Outcome::Valid => accepted,
+            Outcome::Invalid => !accepted,
+            Outcome::Acceptable => true,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MacTestGroup {
+    /// Size of the tags in this group in bits, which may be truncated.
+    tag_size: usize,
+    tests:    Vec<MacTest>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MacTest {
+    tc_id:   u64,
+    comment: String,
+    #[serde(with = "hex::serde")]
+    key:     Vec<u8>,
+    #[serde(with = "hex::serde")]
+    msg:     Vec<u8>,
+    #[serde(with = "hex::serde")]
+    tag:     Vec<u8>,
+    result:  Outcome,
+}
+
+#[derive(Deserialize)]
+struct HkdfTestGroup {
+    tests: Vec<HkdfTest>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HkdfTest {
+    tc_id:   u64,
+    comment: String,
+    #[serde(with = "hex::serde")]
+    ikm:     Vec<u8>,
+    #[serde(with = "hex::serde")]
+    salt:    Vec<u8>,
+    #[serde(with = "hex::serde")]
+    info:    Vec<u8>,
+    size:    usize,
+    #[serde(with = "hex::serde")]
+    okm:     Vec<u8>,
+    result:  Outcome,
+}
+
+fn load(file: &str) -> String {
+    let path = Path::new(DATA_DIR).join(file);
+    fs::read_to_string(&path).unwrap_or_else(|err| panic!("Can't read {}: {}", path.display(), err))
+}
+
+fn parse<G: for<'de> Deserialize<'de>>(json: &str, algorithm: &str) -> Vec<G> {
+    let suite: Suite<G> = serde_json::from_str(json).unwrap();
+    assert_eq!(suite.algorithm, algorithm);
+    suite.test_groups
+}
+
+/// Whether the tag of `test` is accepted, truncated tags being compared to a digest prefix.
+fn hmac_accepts(test: &MacTest, tag_size: usize) -> bool {
+    let mut mac = HmacSha256::new(&test.key);
+    mac.update(&test.msg);
+    if tag_size == 256 {
+        // Tags of any other length, truncated ones included, must be rejected
+        return Mac::verify(mac, &test.tag).into();
+    }
+    // Only tags of exactly the group's size are valid, a longer correct prefix included
+    test.tag.len() == tag_size / 8
+        && matches!(mac.verify_truncated_min(&test.tag, tag_size / 8), Ok(valid) if valid.into())
+}
+
+/// Run a MacTest suite, returning a description of each failed case.
+fn run_hmac_suite(json: &str) -> Vec<String> {
+    let mut failures = Vec::new();
+    for group in parse::<MacTestGroup>(json, "HMACSHA256") {
+        for test in &group.tests {
+            let accepted = hmac_accepts(test, group.tag_size);
+            if !test.result.allows(accepted) {
+                failures.push(format!(
+                    "tcId {} ({}): expected {:?}, tag was {}",
+                    test.tc_id,
+                    test.comment,
+                    test.result,
+                    if accepted { "accepted" } else { "rejected" }
+                ));
+            }
+        }
+    }
+    failures
+}
+
+/// Run an HkdfTest suite, returning a description of each failed case.
+fn run_hkdf_suite(json: &str) -> Vec<String> {
+    let mut failures = Vec::new();
+    for group in parse::<HkdfTestGroup>(json, "HKDF-SHA-256") {
+        for test in &group.tests {
+            let prk = hkdf_extract(Some(&test.salt), &test.ikm);
+            let mut okm = vec![0u8; test.size];
+            let outcome = hkdf_expand(&prk, &test.info, &mut okm).map(|()| okm);
+            let passed = match (&outcome, test.result) {
+                (Ok(okm), Outcome::Valid | Outcome::Acceptable) => *okm == test.okm,
+                (Err(_), Outcome::Invalid | Outcome::Acceptable) => true,
+                _ => false,
+            };
+            if !passed {
+                failures.push(format!(
+                    "tcId {} ({}): expected {:?}, got {:?}",
+                    test.tc_id,
+                    test.comment,
+                    test.result,
+                    outcome.map(hex::encode)
+                ));
+            }
+        }
+    }
+    failures
+}
+
+fn assert_passes(file: &str, run: fn(&str) -> Vec<String>) {
+    let failures = run(&load(file));
+    assert!(failures.is_empty(), "{} failed:\n{}", file, failures.join("\n"));
+}
+
+#[test]
+fn wycheproof_hmac_sha256() { assert_passes("wycheproof/hmac_sha256_test.json", run_hmac_suite); }
+
+#[test]
+fn wycheproof_hkdf_sha256() { assert_passes("wycheproof/hkdf_sha256_test.json", run_hkdf_suite); }
+
+#[test]
+fn rfc4231_hmac_sha256() { assert_passes("rfc/hmac_sha256_rfc4231.json", run_hmac_suite); }
+
+#[test]
+fn rfc5869_hkdf_sha256() { assert_passes("rfc/hkdf_sha256_rfc5869.json", run_hkdf_suite); }
+
+#[test]
+fn wycheproof_failures_name_the_case() {
+    // A suite expecting a tampered tag to pass must fail, reporting the case
+    let json = r#"{"algorithm": "HMACSHA256", "testGroups": [{"tagSize": 256, "tests": [
+        {"tcId": 7, "comment": "tampered", "key": "00", "msg": "",
+         "tag": "0000000000000000000000000000000000000000000000000000000000000000",
+         "result": "valid"}]}]}"#;
+    assert_eq!(run_hmac_suite(json), ["tcId 7 (tampered): expected Valid, tag was rejected"]);
+
+    let json = r#"{"algorithm": "HKDF-SHA-256", "testGroups": [{"tests": [
+        {"tcId": 3, "comment": "too long", "ikm": "00", "salt": "", "info": "", "size": 8161,
+         "okm": "", "result": "valid"}]}]}"#;
+    let failures = run_hkdf_suite(json);
+    assert_eq!(failures.len(), 1);
+    assert!(
+        failures[0].starts_with("tcId 3 (too long): expected Valid, got Err"),
+        "{}",
+        failures[0]
+    );
+}